@@ -0,0 +1,157 @@
+use crate::config::Config;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use xxhash_rust::xxh3::{xxh3_64, xxh3_64_with_seed};
+
+/// Default cache directory, used when the project has a `node_modules`
+/// directory
+pub const DEFAULT_CACHE_DIR: &str = "node_modules/.cache/biome-tailwind-sorter";
+
+const CACHE_FILE: &str = "cache.json";
+
+/// Hashes of files known to be formatted, so they can be skipped on the next
+/// run. A hash covers the file content and the effective config; entries
+/// written by another version of the tool are discarded.
+#[derive(Debug, Default)]
+pub struct Cache {
+    path: PathBuf,
+    entries: HashMap<String, u64>,
+    /// Whether entries were added or removed since loading
+    dirty: bool,
+}
+
+#[derive(Deserialize)]
+struct CacheFile {
+    version: String,
+    entries: HashMap<String, u64>,
+}
+
+impl Cache {
+    /// Load the cache in `dir`, starting empty when it is missing, unreadable
+    /// or from another version
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(CACHE_FILE);
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+            .filter(|file| file.version == env!("CARGO_PKG_VERSION"))
+            .map(|file| file.entries)
+            .unwrap_or_default();
+        Self { path, entries, dirty: false }
+    }
+
+    /// Hash identifying an effective config
+    pub fn config_hash(config: &Config) -> u64 {
+        xxh3_64(serde_json::to_string(config).unwrap_or_default().as_bytes())
+    }
+
+    /// Hash of a file's content formatted with the config of `config_hash`
+    pub fn key(content: &str, config_hash: u64) -> u64 {
+        xxh3_64_with_seed(content.as_bytes(), config_hash)
+    }
+
+    /// Whether `path` was formatted when its content hashed to `key`
+    pub fn is_formatted(&self, path: &Path, key: u64) -> bool {
+        self.entries.get(&entry_name(path)) == Some(&key)
+    }
+
+    /// Record that `path` is formatted with content hashing to `key`
+    pub fn insert(&mut self, path: &Path, key: u64) {
+        if self.entries.insert(entry_name(path), key) != Some(key) {
+            self.dirty = true;
+        }
+    }
+
+    /// Forget a file that may no longer be formatted
+    pub fn remove(&mut self, path: &Path) {
+        if self.entries.remove(&entry_name(path)).is_some() {
+            self.dirty = true;
+        }
+    }
+
+    /// Write the cache back if it changed, dropping entries of files that no
+    /// longer exist
+    pub fn save(&mut self) -> std::io::Result<()> {
+        let before = self.entries.len();
+        self.entries.retain(|path, _| Path::new(path).is_file());
+        if !self.dirty && self.entries.len() == before {
+            return Ok(());
+        }
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content = serde_json::json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "entries": self.entries,
+        });
+
+        // Write to a temporary file first so concurrent runs never read a
+        // partially written cache
+        let temp_path = self.path.with_extension(format!("{}.tmp", std::process::id()));
+        fs::write(&temp_path, content.to_string())?;
+        fs::rename(&temp_path, &self.path)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// Entries are keyed by absolute path, so runs from other directories share
+/// them
+fn entry_name(path: &Path) -> String {
+    fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("index.html");
+        fs::write(&file, "<div class=\"flex p-4\"></div>").unwrap();
+        let deleted = temp_dir.path().join("deleted.html");
+        let cache_dir = temp_dir.path().join("cache");
+
+        let config_hash = Cache::config_hash(&Config::default());
+        let key = Cache::key("<div class=\"flex p-4\"></div>", config_hash);
+        let mut cache = Cache::load(&cache_dir);
+        assert!(!cache.is_formatted(&file, key));
+        cache.insert(&file, key);
+        cache.insert(&deleted, key);
+        cache.save().unwrap();
+
+        let cache = Cache::load(&cache_dir);
+        assert!(cache.is_formatted(&file, key));
+        assert!(!cache.is_formatted(&deleted, key), "Entries of missing files are dropped");
+
+        // Other content or another config misses
+        assert!(!cache.is_formatted(&file, Cache::key("<div></div>", config_hash)));
+        let config = Config { remove_duplicates: true, ..Config::default() };
+        let other_key = Cache::key("<div class=\"flex p-4\"></div>", Cache::config_hash(&config));
+        assert!(!cache.is_formatted(&file, other_key));
+    }
+
+    #[test]
+    fn test_cache_from_other_version_is_discarded() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("index.html");
+        fs::write(&file, "").unwrap();
+        let mut cache = Cache::load(temp_dir.path());
+        cache.insert(&file, 1);
+        cache.save().unwrap();
+        assert!(Cache::load(temp_dir.path()).is_formatted(&file, 1));
+
+        let content = fs::read_to_string(temp_dir.path().join(CACHE_FILE)).unwrap();
+        let version = format!("\"version\":\"{}\"", env!("CARGO_PKG_VERSION"));
+        fs::write(temp_dir.path().join(CACHE_FILE), content.replace(&version, "\"version\":\"0.0.1\"")).unwrap();
+        assert!(!Cache::load(temp_dir.path()).is_formatted(&file, 1));
+    }
+}
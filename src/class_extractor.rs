@@ -1,4 +1,8 @@
+use crate::tailwind_order::parse_tailwind_class;
 use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::LazyLock;
 
 #[derive(Debug, Clone)]
@@ -19,6 +23,21 @@ pub enum QuoteType {
     None,
 }
 
+/// How sorted classes are laid out in multiline class attributes
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum MultilineLayout {
+    /// Spread classes evenly over the original number of lines
+    #[default]
+    Preserve,
+    /// One class per line
+    OnePerLine,
+    /// Layout, spacing, typography, appearance and variants on their own lines
+    GroupPerLine,
+    /// Fill lines up to the print width
+    Wrap,
+}
+
 pub fn extract_class_names(class_string: &str) -> Vec<String> {
     class_string
         .split_whitespace()
@@ -27,43 +46,191 @@ pub fn extract_class_names(class_string: &str) -> Vec<String> {
         .collect()
 }
 
+/// Drop exact duplicate classes, keeping the first occurrence.
+///
+/// Tokens inside template interpolations (`${...}`, `{{ ... }}`, `{...}`) are
+/// never treated as duplicates. Returns the remaining classes and the number
+/// of classes removed.
+pub fn remove_duplicate_classes(class_names: &[String]) -> (Vec<String>, usize) {
+    let mut seen: Vec<&str> = Vec::new();
+    let mut result = Vec::with_capacity(class_names.len());
+    let mut removed = 0;
+    let mut depth = 0usize;
+    
+    for class_name in class_names {
+        let opens = class_name.matches('{').count();
+        let closes = class_name.matches('}').count();
+        let in_interpolation = depth > 0 || opens > 0 || closes > 0;
+        depth = (depth + opens).saturating_sub(closes);
+        
+        if in_interpolation {
+            result.push(class_name.clone());
+            continue;
+        }
+        
+        if seen.contains(&class_name.as_str()) {
+            removed += 1;
+        } else {
+            seen.push(class_name);
+            result.push(class_name.clone());
+        }
+    }
+    
+    (result, removed)
+}
+
+/// Collapse runs of whitespace between classes and trim the ends.
+///
+/// Multiline values keep one line per original line of classes, with the
+/// indentation of each line and of the closing line preserved.
+pub fn normalize_class_whitespace(class_string: &str) -> String {
+    if !class_string.contains('\n') {
+        return extract_class_names(class_string).join(" ");
+    }
+    
+    let lines: Vec<&str> = class_string.split('\n').collect();
+    let last = lines.len() - 1;
+    let mut result = Vec::with_capacity(lines.len());
+    
+    for (i, line) in lines.iter().enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let classes = extract_class_names(line);
+        let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+        
+        if !classes.is_empty() {
+            result.push(format!("{}{}", indent, classes.join(" ")));
+        } else if i == 0 {
+            result.push(String::new());
+        } else if i == last {
+            result.push(indent);
+        }
+    }
+    
+    result.join(line_ending(class_string))
+}
+
+/// The line ending of a multiline class string, so CRLF files keep theirs
+fn line_ending(class_string: &str) -> &'static str {
+    if class_string.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+#[allow(dead_code)]
 pub fn reconstruct_class_string(
     class_names: &[String],
     original_string: &str,
     preserve_multiline: bool,
 ) -> String {
-    if !preserve_multiline || !original_string.contains('\n') {
+    if !preserve_multiline {
         return class_names.join(" ");
     }
-    
-    // For multiline, try to preserve the original formatting structure
+    layout_class_string(class_names, original_string, MultilineLayout::Preserve, 0, 0)
+}
+
+/// Lay out sorted classes for a multiline attribute.
+///
+/// Single-line values are always joined with single spaces. For multiline
+/// values the whitespace before the first class and after the last class
+/// (the opening and closing indentation) is kept as is. `first_column` is
+/// the column the value starts at, which the wrap layout needs when the
+/// first line continues after the opening quote.
+pub fn layout_class_string(
+    class_names: &[String],
+    original_string: &str,
+    layout: MultilineLayout,
+    print_width: usize,
+    first_column: usize,
+) -> String {
     let lines: Vec<&str> = original_string.split('\n').collect();
-    if lines.len() <= 1 {
+    if lines.len() <= 1 || class_names.is_empty() {
         return class_names.join(" ");
     }
     
-    // Simple heuristic: distribute classes evenly across lines
-    let classes_per_line = class_names.len().div_ceil(lines.len()); // Ceiling division
-    let mut result = Vec::new();
+    let content = original_string.trim();
+    let leading = &original_string[..original_string.len() - original_string.trim_start().len()];
+    let trailing = &original_string[original_string.trim_end().len()..];
     
-    for (i, line) in lines.iter().enumerate() {
-        let line_start = i * classes_per_line;
-        let line_end = std::cmp::min((i + 1) * classes_per_line, class_names.len());
-        
-        if line_start < class_names.len() {
-            let line_classes = &class_names[line_start..line_end];
-            
-            if !line_classes.is_empty() {
-                // Extract the indentation from the original line
-                let indent = line.chars()
-                    .take_while(|c| c.is_whitespace())
-                    .collect::<String>();
-                result.push(format!("{}{}", indent, line_classes.join(" ")));
+    // Indentation of the lines holding classes
+    let class_lines: Vec<&str> = content.split('\n').collect();
+    let indent: &str = if leading.contains('\n') {
+        leading.rsplit('\n').next().unwrap_or("")
+    } else {
+        class_lines.get(1).map_or("", |line| {
+            &line[..line.len() - line.trim_start().len()]
+        })
+    };
+    
+    let output_lines: Vec<String> = match layout {
+        MultilineLayout::Preserve => {
+            // Distribute classes evenly across the original number of lines
+            let line_count = class_lines
+                .iter()
+                .filter(|line| !line.trim().is_empty())
+                .count()
+                .max(1);
+            let classes_per_line = class_names.len().div_ceil(line_count);
+            class_names
+                .chunks(classes_per_line)
+                .map(|chunk| chunk.join(" "))
+                .collect()
+        }
+        MultilineLayout::OnePerLine => class_names.to_vec(),
+        MultilineLayout::GroupPerLine => {
+            // The sorter interleaves variants with their base utilities, so
+            // bucket by group instead of splitting runs of the sorted list
+            let mut groups: BTreeMap<u8, Vec<&str>> = BTreeMap::new();
+            for class_name in class_names {
+                groups.entry(layout_group(class_name)).or_default().push(class_name);
             }
+            groups.into_values().map(|line| line.join(" ")).collect()
         }
-    }
+        MultilineLayout::Wrap => {
+            let mut wrapped: Vec<String> = Vec::new();
+            let mut line = String::new();
+            let mut line_start = if leading.contains('\n') {
+                indent.len()
+            } else {
+                first_column + leading.len()
+            };
+            for class_name in class_names {
+                if !line.is_empty() && line_start + line.len() + 1 + class_name.len() > print_width {
+                    wrapped.push(std::mem::take(&mut line));
+                    line_start = indent.len();
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(class_name);
+            }
+            wrapped.push(line);
+            wrapped
+        }
+    };
     
-    result.join("\n")
+    format!(
+        "{}{}{}",
+        leading,
+        output_lines.join(&format!("{}{indent}", line_ending(original_string))),
+        trailing
+    )
+}
+
+/// Line group used by [`MultilineLayout::GroupPerLine`]
+fn layout_group(class_name: &str) -> u8 {
+    let class = parse_tailwind_class(class_name);
+    if class.modifier.is_some() {
+        return 4; // variants
+    }
+    match class.order {
+        0..=70 => 0,   // layout, position, flexbox and grid
+        80..=90 => 1,  // spacing and sizing
+        100 => 2,      // typography
+        110..=210 => 3, // backgrounds, borders, effects, ...
+        _ => 5,        // unknown and custom classes
+    }
 }
 
 #[allow(dead_code)]
@@ -135,6 +302,7 @@ pub fn is_tailwind_class(class_name: &str) -> bool {
 }
 
 
+#[allow(dead_code)]
 pub fn contains_tailwind_classes(class_names: &[String]) -> bool {
     class_names.iter().any(|class_name| is_tailwind_class(class_name))
 }
@@ -157,6 +325,34 @@ mod tests {
         assert_eq!(result, vec!["flex", "items-center", "justify-between"]);
     }
 
+    #[test]
+    fn test_remove_duplicate_classes() {
+        let input = extract_class_names("p-4 flex p-4 flex text-sm");
+        let (result, removed) = remove_duplicate_classes(&input);
+        assert_eq!(result, vec!["p-4", "flex", "text-sm"]);
+        assert_eq!(removed, 2);
+    }
+
+    #[test]
+    fn test_remove_duplicate_classes_keeps_interpolations() {
+        let input = extract_class_names("p-4 ${active ? 'p-4' : 'p-2'} p-4 {{ p-4 }}");
+        let (result, removed) = remove_duplicate_classes(&input);
+        assert_eq!(
+            result,
+            vec!["p-4", "${active", "?", "'p-4'", ":", "'p-2'}", "{{", "p-4", "}}"]
+        );
+        assert_eq!(removed, 1);
+    }
+
+    #[test]
+    fn test_normalize_class_whitespace() {
+        assert_eq!(normalize_class_whitespace(" flex \t p-4  "), "flex p-4");
+        assert_eq!(
+            normalize_class_whitespace(" \n    flex   p-4  \n\n    text-sm\n  "),
+            "\n    flex p-4\n    text-sm\n  "
+        );
+    }
+
     #[test]
     fn test_parse_class_attribute_with_quotes() {
         let result = parse_class_attribute("\"flex items-center\"", "class").unwrap();
@@ -222,4 +418,76 @@ mod tests {
         assert!(result.contains("  flex"));
         assert!(result.contains('\n'));
     }
-}
\ No newline at end of file
+
+    fn classes(input: &str) -> Vec<String> {
+        extract_class_names(input)
+    }
+
+    #[test]
+    fn test_reconstruct_keeps_opening_and_closing_indentation() {
+        let original = "\n    text-white\n    p-4\n    flex\n  ";
+        let result = reconstruct_class_string(&classes("flex p-4 text-white"), original, true);
+        assert_eq!(result, "\n    flex\n    p-4\n    text-white\n  ");
+    }
+
+    #[test]
+    fn test_crlf_line_endings_are_kept() {
+        let original = "\r\n    p-4 flex\r\n  ";
+        let result = layout_class_string(&classes("flex p-4"), original, MultilineLayout::OnePerLine, 80, 0);
+        assert_eq!(result, "\r\n    flex\r\n    p-4\r\n  ");
+        assert_eq!(normalize_class_whitespace("\r\n    flex   p-4\r\n\r\n  "), "\r\n    flex p-4\r\n  ");
+    }
+
+    #[test]
+    fn test_layout_one_per_line() {
+        let original = "\n    p-4 flex\n  ";
+        let result = layout_class_string(&classes("flex p-4"), original, MultilineLayout::OnePerLine, 80, 0);
+        assert_eq!(result, "\n    flex\n    p-4\n  ");
+    }
+
+    #[test]
+    fn test_layout_group_per_line() {
+        let original = "\n  text-sm p-4 flex\n  hover:bg-red-500 m-2 relative\n";
+        let result = layout_class_string(
+            &classes("flex relative m-2 p-4 text-sm hover:bg-red-500"),
+            original,
+            MultilineLayout::GroupPerLine,
+            80,
+            0,
+        );
+        assert_eq!(result, "\n  flex relative\n  m-2 p-4\n  text-sm\n  hover:bg-red-500\n");
+
+        let original = "\n  text-sm flex p-4\n  md:p-6 hover:text-red-500 md:flex-col\n";
+        let result = layout_class_string(
+            &classes("flex md:flex-col p-4 md:p-6 text-sm hover:text-red-500"),
+            original,
+            MultilineLayout::GroupPerLine,
+            80,
+            0,
+        );
+        assert_eq!(result, "\n  flex\n  p-4\n  text-sm\n  md:flex-col md:p-6 hover:text-red-500\n");
+    }
+
+    #[test]
+    fn test_layout_wrap() {
+        let original = "flex\n      p-4";
+        let result = layout_class_string(
+            &classes("flex items-center p-4 text-sm"),
+            original,
+            MultilineLayout::Wrap,
+            24,
+            6,
+        );
+        assert_eq!(result, "flex items-center\n      p-4 text-sm");
+
+        // Starting further right leaves less room on the first line
+        let result = layout_class_string(
+            &classes("flex items-center p-4 text-sm"),
+            original,
+            MultilineLayout::Wrap,
+            24,
+            12,
+        );
+        assert_eq!(result, "flex\n      items-center p-4\n      text-sm");
+    }
+}
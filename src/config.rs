@@ -1,9 +1,23 @@
+use crate::class_extractor::MultilineLayout;
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use regex::Regex;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::LazyLock;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Configuration of biome-tailwind-sorter
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
+    /// URL or path of the JSON Schema, for editors
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    
     /// Custom class order (overrides default Tailwind order)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_order: Option<Vec<String>>,
@@ -16,10 +30,30 @@ pub struct Config {
     #[serde(default = "default_preserve_multiline")]
     pub preserve_multiline: bool,
     
-    /// Directories/files to ignore
+    /// Layout of multiline class attributes: preserve, one-per-line, group-per-line or wrap
+    #[serde(default)]
+    pub multiline_layout: MultilineLayout,
+    
+    /// Maximum line width for the wrap layout
+    #[serde(default = "default_print_width")]
+    pub print_width: usize,
+    
+    /// Glob patterns of directories/files to ignore; bare names match any path component
     #[serde(default)]
     pub ignore: Vec<String>,
     
+    /// Glob patterns a file must match to be processed (all files when empty)
+    #[serde(default)]
+    pub include: Vec<String>,
+    
+    /// Additional attributes holding classes, besides `class` and `className`
+    #[serde(default)]
+    pub attributes: Vec<String>,
+    
+    /// Functions whose string arguments hold classes, e.g. `clsx` or `cn`
+    #[serde(default)]
+    pub functions: Vec<String>,
+    
     /// Whether to process files recursively in directories
     #[serde(default = "default_recursive")]
     pub recursive: bool,
@@ -35,6 +69,74 @@ pub struct Config {
     /// Whether to sort custom classes
     #[serde(default = "default_sort_custom_classes")]
     pub sort_custom_classes: bool,
+    
+    /// Whether to drop exact duplicate classes
+    #[serde(default)]
+    pub remove_duplicates: bool,
+    
+    /// Whether to collapse class pairs into shorthands (e.g. `mt-2 mb-2` to `my-2`)
+    #[serde(default)]
+    pub fix_shorthands: bool,
+    
+    /// Whether to collapse runs of whitespace in class attributes, even when already sorted
+    #[serde(default)]
+    pub normalize_whitespace: bool,
+    
+    /// Directory of the formatting cache, relative to the config's directory
+    /// (`node_modules/.cache/biome-tailwind-sorter` by default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<String>,
+    
+    /// Config files this one builds on, relative to its directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "string_or_list")]
+    #[schemars(with = "StringOrList")]
+    pub extends: Vec<String>,
+    
+    /// Settings for files matching glob patterns relative to the config's directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<ConfigOverride>,
+    
+    /// `include` and `ignore` compiled once, relative to the directory of
+    /// the config file setting them (see [`ConfigResolver`])
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub(crate) patterns: Option<ConfigPatterns>,
+}
+
+/// The compiled `include` and `ignore` patterns of a config
+#[derive(Debug, Clone)]
+pub(crate) struct ConfigPatterns {
+    include: PathPatterns,
+    ignore: PathPatterns,
+}
+
+/// An `overrides` entry: settings applied on top of the config for the
+/// files matching any of `files`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigOverride {
+    pub files: Vec<String>,
+    
+    #[serde(flatten)]
+    pub settings: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A single string or a list of strings
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum StringOrList {
+    String(String),
+    List(Vec<String>),
+}
+
+/// Accept either a single string or a list of strings
+fn string_or_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match StringOrList::deserialize(deserializer)? {
+        StringOrList::String(value) => vec![value],
+        StringOrList::List(values) => values,
+    })
 }
 
 fn default_extensions() -> Vec<String> {
@@ -53,6 +155,10 @@ fn default_preserve_multiline() -> bool {
     true
 }
 
+fn default_print_width() -> usize {
+    80
+}
+
 fn default_recursive() -> bool {
     true
 }
@@ -68,9 +174,12 @@ fn default_sort_custom_classes() -> bool {
 impl Default for Config {
     fn default() -> Self {
         Self {
+            schema: None,
             custom_order: None,
             extensions: default_extensions(),
             preserve_multiline: default_preserve_multiline(),
+            multiline_layout: MultilineLayout::default(),
+            print_width: default_print_width(),
             ignore: vec![
                 "node_modules".to_string(),
                 ".git".to_string(),
@@ -78,85 +187,860 @@ impl Default for Config {
                 "build".to_string(),
                 "target".to_string(),
             ],
+            include: Vec::new(),
+            attributes: Vec::new(),
+            functions: Vec::new(),
             recursive: default_recursive(),
             max_file_size: default_max_file_size(),
             custom_classes: Vec::new(),
             sort_custom_classes: default_sort_custom_classes(),
+            remove_duplicates: false,
+            fix_shorthands: false,
+            normalize_whitespace: false,
+            cache_dir: None,
+            extends: Vec::new(),
+            overrides: Vec::new(),
+            patterns: None,
+        }
+    }
+}
+
+/// Configuration file names looked up by [`Config::load`], in order
+pub const CONFIG_FILES: [&str; 6] = [
+    ".tailwindsorterrc",
+    ".tailwindsorterrc.json",
+    ".tailwindsorterrc.jsonc",
+    "tailwindsorter.toml",
+    "tailwindsorter.config.json",
+    "biome-tailwind-sorter.json",
+];
+
+/// Error loading a configuration file, with the location of the problem
+/// when it is known
+#[derive(Debug, Clone)]
+pub struct ConfigError {
+    pub path: PathBuf,
+    /// 1-based line of the problem
+    pub line: Option<usize>,
+    /// 1-based column of the problem
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl ConfigError {
+    fn new(path: &Path, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            line: None,
+            column: None,
+            message: with_suggestion(message.into()),
+        }
+    }
+
+    fn at(path: &Path, line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(line),
+            column: Some(column),
+            ..Self::new(path, message)
+        }
+    }
+
+    fn from_json(path: &Path, err: serde_json::Error) -> Self {
+        // serde_json appends the location to its message; report it separately
+        let message = err.to_string();
+        let message = match message.rfind(" at line ") {
+            Some(index) => message[..index].to_string(),
+            None => message,
+        };
+        Self::at(path, err.line(), err.column(), message)
+    }
+
+    fn from_toml(path: &Path, content: &str, err: toml::de::Error) -> Self {
+        let message = err.message().to_string();
+        match err.span() {
+            Some(span) => {
+                let (line, column) = line_column(content, span.start);
+                Self::at(path, line, column, message)
+            }
+            None => Self::new(path, message),
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => {
+                write!(f, "{}:{}:{}: {}", self.path.display(), line, column, self.message)
+            }
+            _ => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+static UNKNOWN_NAME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"unknown (?:field|variant) `([^`]*)`, expected (?:one of )?(.*)").unwrap()
+});
+
+/// Replace the list of expected names in serde's "unknown field" and
+/// "unknown variant" errors with the closest one, when there is one
+fn with_suggestion(message: String) -> String {
+    let Some(captures) = UNKNOWN_NAME_REGEX.captures(&message) else {
+        return message;
+    };
+    let unknown = &captures[1];
+    let expected = captures[2].split('`').skip(1).step_by(2);
+    
+    let suggestion = expected
+        .map(|name| (strsim::levenshtein(unknown, name), name))
+        .filter(|(distance, name)| *distance <= (name.len() / 3).max(2))
+        .min_by_key(|(distance, _)| *distance);
+    match suggestion {
+        Some((_, name)) => {
+            let whole = captures.get(0).unwrap();
+            let kind = if whole.as_str().starts_with("unknown field") { "field" } else { "value" };
+            format!(
+                "{}unknown {kind} `{unknown}`, did you mean `{name}`?",
+                &message[..whole.start()]
+            )
+        }
+        None => message,
+    }
+}
+
+/// 1-based line and column of a byte offset
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
+
+/// Turn JSON with comments and trailing commas into plain JSON.
+///
+/// Comments and trailing commas are replaced with spaces so that error
+/// locations still point into the original text.
+pub fn strip_jsonc(content: &str) -> String {
+    let bytes = content.as_bytes();
+    let mut result = bytes.to_vec();
+    let mut i = 0;
+    let mut in_string = false;
+    
+    while i < bytes.len() {
+        let byte = bytes[i];
+        
+        if in_string {
+            match byte {
+                b'\\' => i += 1,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            i += 1;
+            continue;
+        }
+        
+        match byte {
+            b'"' => in_string = true,
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    result[i] = b' ';
+                    i += 1;
+                }
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let start = i;
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                let end = (i + 2).min(bytes.len());
+                for b in &mut result[start..end] {
+                    if *b != b'\n' {
+                        *b = b' ';
+                    }
+                }
+                i = end;
+                continue;
+            }
+            b',' => {
+                // A comma followed only by whitespace/comments before a closing bracket
+                let mut j = i + 1;
+                while j < bytes.len() {
+                    if bytes[j].is_ascii_whitespace() {
+                        j += 1;
+                    } else if bytes[j] == b'/' && bytes.get(j + 1) == Some(&b'/') {
+                        while j < bytes.len() && bytes[j] != b'\n' {
+                            j += 1;
+                        }
+                    } else if bytes[j] == b'/' && bytes.get(j + 1) == Some(&b'*') {
+                        j += 2;
+                        while j < bytes.len() && !(bytes[j] == b'*' && bytes.get(j + 1) == Some(&b'/')) {
+                            j += 1;
+                        }
+                        j += 2;
+                    } else {
+                        break;
+                    }
+                }
+                if matches!(bytes.get(j), Some(b'}') | Some(b']')) {
+                    result[i] = b' ';
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    
+    // Only ASCII bytes outside strings were replaced, so this stays valid UTF-8
+    String::from_utf8(result).unwrap_or_else(|_| content.to_string())
+}
+
+/// Configuration file formats, chosen by file extension
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+    Json,
+    Jsonc,
+    Toml,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => ConfigFormat::Toml,
+            Some("jsonc") => ConfigFormat::Jsonc,
+            _ => ConfigFormat::Json,
+        }
+    }
+}
+
+/// Recursively merge `overlay` into `base`; objects are merged key by key,
+/// anything else in `overlay` replaces the value in `base`
+pub fn merge_values(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Move the `overrides` entries of a raw config value into `overrides`
+fn take_overrides(value: &mut serde_json::Value, overrides: &mut Vec<serde_json::Value>) {
+    if let Some(serde_json::Value::Array(entries)) =
+        value.as_object_mut().and_then(|object| object.remove("overrides"))
+    {
+        overrides.extend(entries);
+    }
+}
+
+/// Read the sorting-related settings of `biome.json`/`biome.jsonc` in `dir`
+/// as a partial config value.
+///
+/// Uses `linter.rules.nursery.useSortedClasses.options` (`attributes` and
+/// `functions`) and `files.include`/`files.ignore` (or Biome 2's
+/// `files.includes`, where `!` marks an ignore pattern).
+pub fn load_biome_settings(dir: &Path) -> Result<Option<(serde_json::Value, PathBuf)>, ConfigError> {
+    let Some(path) = ["biome.json", "biome.jsonc"]
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+    else {
+        return Ok(None);
+    };
+    
+    let content = fs::read_to_string(&path)
+        .map_err(|e| ConfigError::new(&path, format!("cannot read file: {e}")))?;
+    // Biome accepts comments in biome.json as well
+    let biome: serde_json::Value = serde_json::from_str(&strip_jsonc(&content))
+        .map_err(|e| ConfigError::from_json(&path, e))?;
+    
+    let strings = |value: Option<&serde_json::Value>| -> Vec<String> {
+        value
+            .and_then(|v| v.as_array())
+            .map(|items| items.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+            .unwrap_or_default()
+    };
+    
+    let mut settings = serde_json::Map::new();
+    let options = biome.pointer("/linter/rules/nursery/useSortedClasses/options");
+    if let Some(options) = options {
+        settings.insert("attributes".into(), strings(options.get("attributes")).into());
+        settings.insert("functions".into(), strings(options.get("functions")).into());
+    }
+    
+    if let Some(files) = biome.get("files") {
+        let mut include = strings(files.get("include"));
+        let mut ignore = strings(files.get("ignore"));
+        for pattern in strings(files.get("includes")) {
+            match pattern.strip_prefix('!') {
+                Some(negated) => ignore.push(negated.trim_start_matches('!').to_string()),
+                None => include.push(pattern),
+            }
+        }
+        
+        // Biome's ignores come on top of the default ones
+        if !ignore.is_empty() {
+            let mut all_ignores = Config::default().ignore;
+            all_ignores.extend(ignore);
+            settings.insert("ignore".into(), all_ignores.into());
+        }
+        // Biome 2 uses "**" to mean everything
+        include.retain(|pattern| pattern != "**");
+        if !include.is_empty() {
+            settings.insert("include".into(), include.into());
+        }
+    }
+    
+    if settings.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some((serde_json::Value::Object(settings), path)))
+}
+
+/// The configuration found in one directory, with `extends` resolved and
+/// `overrides` kept apart so their patterns stay relative to `dir`
+#[derive(Debug)]
+struct ConfigLayer {
+    dir: PathBuf,
+    source: PathBuf,
+    value: serde_json::Value,
+    /// Each `overrides` entry with its compiled `files` patterns
+    overrides: Vec<(PathPatterns, ConfigOverride)>,
+}
+
+impl ConfigLayer {
+    fn new(dir: PathBuf, source: PathBuf, mut value: serde_json::Value) -> Result<Self, ConfigError> {
+        let overrides: Vec<ConfigOverride> = match value.as_object_mut().and_then(|object| object.remove("overrides")) {
+            Some(overrides) => serde_json::from_value(overrides)
+                .map_err(|e| ConfigError::new(&source, format!("\"overrides\": {e}")))?,
+            None => Vec::new(),
+        };
+        for (index, entry) in overrides.iter().enumerate() {
+            serde_json::from_value::<Config>(serde_json::Value::Object(entry.settings.clone()))
+                .map_err(|e| ConfigError::new(&source, format!("\"overrides\"[{index}]: {e}")))?;
+        }
+        let overrides = overrides.into_iter().map(|entry| (PathPatterns::new(&entry.files), entry)).collect();
+        Ok(Self { dir, source, value, overrides })
+    }
+    
+    /// Settings of the overrides whose patterns match `file`, in order
+    fn matching_overrides<'a>(&'a self, file: &'a Path) -> impl Iterator<Item = &'a serde_json::Map<String, serde_json::Value>> {
+        let relative = file.strip_prefix(&self.dir).unwrap_or(file);
+        self.overrides
+            .iter()
+            .filter(move |(patterns, _)| patterns.matches_relative(relative))
+            .map(|(_, entry)| &entry.settings)
+    }
+}
+
+/// Resolves the configuration of each file.
+///
+/// Config files are looked up from the file's directory up to the root of
+/// the filesystem; settings of nearer files take precedence over those
+/// further up. The `overrides` of every level are applied on top, for the
+/// files matching their patterns. Directories and resolved configs are
+/// cached, so files sharing a config share the same [`Rc`].
+#[derive(Debug, Default)]
+pub struct ConfigResolver {
+    /// A config given on the command line, replacing discovery
+    explicit: Option<Rc<ConfigLayer>>,
+    chains: HashMap<PathBuf, Rc<Vec<Rc<ConfigLayer>>>>,
+    configs: HashMap<String, Rc<Config>>,
+}
+
+impl ConfigResolver {
+    /// Resolver discovering config files next to the files being processed
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Resolver using a single config file for every file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let value = Config::load_extended_value(path, &mut Vec::new())?;
+        let dir = absolute_dir(path.parent().unwrap_or(Path::new("")));
+        Ok(Self {
+            explicit: Some(Rc::new(ConfigLayer::new(dir, path.to_path_buf(), value)?)),
+            ..Self::default()
+        })
+    }
+    
+    /// The config for a file
+    pub fn resolve(&mut self, file: &Path) -> Result<Rc<Config>, ConfigError> {
+        let dir = absolute_dir(file.parent().unwrap_or(Path::new("")));
+        let file = match file.file_name() {
+            Some(name) => dir.join(name),
+            None => dir.clone(),
+        };
+        let chain = self.chain(&dir)?;
+        self.build(&chain, Some(&file))
+    }
+    
+    /// The config for a directory, without applying any `overrides`, and
+    /// the nearest file it was loaded from
+    pub fn resolve_dir(&mut self, dir: &Path) -> Result<(Rc<Config>, Option<PathBuf>), ConfigError> {
+        let chain = self.chain(&absolute_dir(dir))?;
+        let source = chain.last().map(|layer| layer.source.clone());
+        Ok((self.build(&chain, None)?, source))
+    }
+    
+    /// The layers that apply to `dir`, outermost first
+    fn chain(&mut self, dir: &Path) -> Result<Rc<Vec<Rc<ConfigLayer>>>, ConfigError> {
+        if let Some(explicit) = &self.explicit {
+            return Ok(Rc::new(vec![explicit.clone()]));
+        }
+        if let Some(chain) = self.chains.get(dir) {
+            return Ok(chain.clone());
+        }
+        
+        let mut chain = match dir.parent() {
+            Some(parent) => self.chain(parent)?.as_ref().clone(),
+            None => Vec::new(),
+        };
+        if let Some(layer) = Config::load_dir_layer(dir)? {
+            chain.push(Rc::new(layer));
+        }
+        let chain = Rc::new(chain);
+        self.chains.insert(dir.to_path_buf(), chain.clone());
+        Ok(chain)
+    }
+    
+    fn build(&mut self, chain: &[Rc<ConfigLayer>], file: Option<&Path>) -> Result<Rc<Config>, ConfigError> {
+        let mut value = serde_json::Value::Object(serde_json::Map::new());
+        for layer in chain {
+            merge_values(&mut value, layer.value.clone());
+            if let Some(file) = file {
+                for settings in layer.matching_overrides(file) {
+                    merge_values(&mut value, serde_json::Value::Object(settings.clone()));
+                }
+            }
+        }
+        
+        // Path patterns are relative to the directory of the layer setting them
+        let pattern_dir = |key: &str| {
+            chain.iter().rev().find_map(|layer| {
+                let in_overrides = file.is_some_and(|file| {
+                    layer.matching_overrides(file).any(|settings| settings.contains_key(key))
+                });
+                (in_overrides || layer.value.get(key).is_some()).then_some(layer.dir.as_path())
+            })
+        };
+        let (include_dir, ignore_dir) = (pattern_dir("include"), pattern_dir("ignore"));
+        
+        let key = format!("{value}{include_dir:?}{ignore_dir:?}");
+        if let Some(config) = self.configs.get(&key) {
+            return Ok(config.clone());
+        }
+        let mut config = serde_json::from_value::<Config>(value).map_err(|e| {
+            let source = chain.last().map_or(Path::new("."), |layer| layer.source.as_path());
+            ConfigError::new(source, e.to_string())
+        })?;
+        config.patterns = Some(ConfigPatterns {
+            include: PathPatterns::with_base(&config.include, include_dir),
+            ignore: PathPatterns::with_base(&config.ignore, ignore_dir),
+        });
+        let config = Rc::new(config);
+        self.configs.insert(key, config.clone());
+        Ok(config)
+    }
+}
+
+/// Compiled `include`, `ignore` or `overrides` patterns, with gitignore-like
+/// semantics.
+///
+/// A pattern without a `/` matches any single path component, so `build`
+/// matches `build/app.js` and `src/build/app.js` but not `src/builder`.
+/// Other patterns match the path relative to their base directory (the
+/// directory of the config file defining them, or the working directory),
+/// or any of its parent directories; `*` does not cross `/` but `**` does.
+#[derive(Debug, Clone)]
+pub struct PathPatterns {
+    /// The patterns as written, to tell whether they are still current
+    sources: Vec<String>,
+    groups: Vec<PatternGroup>,
+}
+
+/// Patterns sharing a base directory
+#[derive(Debug, Clone)]
+struct PatternGroup {
+    /// The working directory when `None`
+    base: Option<PathBuf>,
+    names: GlobSet,
+    paths: GlobSet,
+}
+
+impl PathPatterns {
+    /// Compile patterns relative to the working directory, skipping invalid
+    /// ones (config files are validated when loaded)
+    pub fn new(patterns: &[String]) -> Self {
+        Self::with_base(patterns, None)
+    }
+    
+    /// Compile patterns relative to `base`, or to the working directory
+    pub fn with_base(patterns: &[String], base: Option<&Path>) -> Self {
+        let mut names = GlobSetBuilder::new();
+        let mut paths = GlobSetBuilder::new();
+        for pattern in patterns {
+            if let Ok((glob, is_path)) = Self::compile(pattern) {
+                if is_path {
+                    paths.add(glob);
+                } else {
+                    names.add(glob);
+                }
+            }
+        }
+        let group = PatternGroup {
+            base: base.map(Path::to_path_buf),
+            names: names.build().unwrap_or_else(|_| GlobSet::empty()),
+            paths: paths.build().unwrap_or_else(|_| GlobSet::empty()),
+        };
+        Self { sources: patterns.to_vec(), groups: vec![group] }
+    }
+    
+    /// Add patterns that may have another base directory
+    pub fn extend(&mut self, other: PathPatterns) {
+        self.sources.extend(other.sources);
+        self.groups.extend(other.groups);
+    }
+    
+    /// Check that a pattern compiles, with the problem when it doesn't
+    pub fn validate(pattern: &str) -> Result<(), String> {
+        Self::compile(pattern).map(|_| ()).map_err(|err| err.kind().to_string())
+    }
+    
+    /// Compile one pattern, and whether it matches whole paths rather than
+    /// names
+    fn compile(pattern: &str) -> Result<(Glob, bool), globset::Error> {
+        let pattern = pattern.trim_start_matches("./").trim_start_matches('/').trim_end_matches('/');
+        let glob = GlobBuilder::new(pattern).literal_separator(true).build()?;
+        Ok((glob, pattern.contains('/')))
+    }
+    
+    /// Check a path, relative to the working directory unless absolute
+    pub fn is_match(&self, path: &Path) -> bool {
+        self.groups.iter().any(|group| {
+            let relative = match &group.base {
+                Some(base) => relative_to_dir(path, base),
+                None => relative_to_cwd(path),
+            };
+            group.is_match(&relative)
+        })
+    }
+    
+    /// Check a path that is already relative to the patterns' base directory
+    pub fn matches_relative(&self, relative: &Path) -> bool {
+        self.groups.iter().any(|group| group.is_match(relative))
+    }
+}
+
+impl PatternGroup {
+    fn is_match(&self, relative: &Path) -> bool {
+        if relative.components().any(|component| self.names.is_match(component.as_os_str())) {
+            return true;
         }
+        relative.ancestors().any(|ancestor| {
+            !ancestor.as_os_str().is_empty() && self.paths.is_match(ancestor)
+        })
+    }
+}
+
+/// A path relative to `dir` when it is below it, and relative to the
+/// working directory otherwise
+fn relative_to_dir(path: &Path, dir: &Path) -> PathBuf {
+    let absolute: PathBuf = match std::env::current_dir() {
+        Ok(cwd) if path.is_relative() => cwd.join(path),
+        _ => path.to_path_buf(),
     }
+    .components()
+    .filter(|component| !matches!(component, std::path::Component::CurDir))
+    .collect();
+    match absolute.strip_prefix(dir) {
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => relative_to_cwd(path),
+    }
+}
+
+/// A path relative to the working directory when it is below it, without
+/// a leading `./`
+pub fn relative_to_cwd(path: &Path) -> PathBuf {
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok())
+        .unwrap_or(path);
+    relative
+        .components()
+        .filter(|component| !matches!(component, std::path::Component::CurDir))
+        .collect()
+}
+
+/// Absolute, canonical form of a directory for caching and matching
+fn absolute_dir(dir: &Path) -> PathBuf {
+    let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+    dir.canonicalize().unwrap_or_else(|_| {
+        std::env::current_dir().map(|cwd| cwd.join(dir)).unwrap_or_else(|_| dir.to_path_buf())
+    })
 }
 
 impl Config {
-    /// Load configuration from a file
+    /// Load configuration from a file, choosing the format by extension.
+    /// Files listed in `extends` are loaded first and overridden by this one.
     #[allow(dead_code)]
-    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Config, Box<dyn std::error::Error>> {
-        let content = fs::read_to_string(path)?;
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Config, ConfigError> {
+        let path = path.as_ref();
+        let value = Self::load_extended_value(path, &mut Vec::new())?;
+        serde_json::from_value(value).map_err(|e| ConfigError::new(path, e.to_string()))
+    }
+    
+    /// Parse and validate configuration text in the format implied by `path`
+    pub fn parse(path: &Path, content: &str) -> Result<Config, ConfigError> {
+        let config: Config = match ConfigFormat::from_path(path) {
+            ConfigFormat::Json => serde_json::from_str(content)
+                .map_err(|e| ConfigError::from_json(path, e))?,
+            ConfigFormat::Jsonc => serde_json::from_str(&strip_jsonc(content))
+                .map_err(|e| ConfigError::from_json(path, e))?,
+            ConfigFormat::Toml => toml::from_str(content)
+                .map_err(|e| ConfigError::from_toml(path, content, e))?,
+        };
         
-        // Try JSON first, then TOML
-        if let Ok(config) = serde_json::from_str::<Config>(&content) {
-            Ok(config)
-        } else {
-            // For future TOML support
-            Err("Only JSON configuration is currently supported".into())
+        config.validate_globs().map_err(|(pattern, message)| {
+            match content.find(&format!("\"{pattern}\"")) {
+                Some(offset) => {
+                    let (line, column) = line_column(content, offset);
+                    ConfigError::at(path, line, column, message)
+                }
+                None => ConfigError::new(path, message),
+            }
+        })?;
+        Ok(config)
+    }
+    
+    /// Check that the `include`, `ignore` and `overrides` patterns are valid
+    /// globs, returning the first invalid pattern and the problem
+    pub fn validate_globs(&self) -> Result<(), (String, String)> {
+        let patterns = self
+            .include
+            .iter()
+            .chain(&self.ignore)
+            .chain(self.overrides.iter().flat_map(|entry| entry.files.iter()));
+        for pattern in patterns {
+            if let Err(err) = PathPatterns::validate(pattern) {
+                return Err((pattern.clone(), format!("invalid glob `{pattern}`: {err}")));
+            }
         }
+        Ok(())
+    }
+    
+    /// JSON Schema of the configuration file, for editor autocompletion
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(Config)).unwrap_or_default()
     }
     
     /// Find and load configuration from common locations
     #[allow(dead_code)]
-    pub fn load() -> Config {
-        let config_files = [
-            ".tailwindsorterrc",
-            ".tailwindsorterrc.json",
-            "tailwindsorter.config.json",
-            "biome-tailwind-sorter.json",
-        ];
+    pub fn load() -> Result<Config, ConfigError> {
+        Self::load_with_source().map(|(config, _)| config)
+    }
+    
+    /// Find and load the configuration of the current directory, looking
+    /// in parent directories too, also returning the nearest file it was
+    /// loaded from. A config file that exists but cannot be parsed is an
+    /// error rather than falling back to the defaults.
+    #[allow(dead_code)]
+    pub fn load_with_source() -> Result<(Config, Option<PathBuf>), ConfigError> {
+        let (config, source) = ConfigResolver::new().resolve_dir(Path::new("."))?;
+        Ok((config.as_ref().clone(), source))
+    }
+    
+    /// Load the configuration found directly in `dir`, if any.
+    ///
+    /// Settings from `biome.json` (`useSortedClasses` options and `files`)
+    /// are used as a base that an explicit config file overrides.
+    fn load_dir_layer(dir: &Path) -> Result<Option<ConfigLayer>, ConfigError> {
+        let biome = load_biome_settings(dir)?;
+        let mut explicit = None;
         
-        for config_file in &config_files {
-            if let Ok(config) = Self::load_from_file(config_file) {
-                return config;
+        for config_file in &CONFIG_FILES {
+            let path = dir.join(config_file);
+            if path.is_file() {
+                explicit = Some((Self::load_extended_value(&path, &mut Vec::new())?, path));
+                break;
             }
         }
         
         // Try package.json
-        if let Ok(package_json) = fs::read_to_string("package.json") {
-            if let Ok(package_data) = serde_json::from_str::<serde_json::Value>(&package_json) {
+        let package_path = dir.join("package.json");
+        if explicit.is_none() {
+            if let Ok(package_json) = fs::read_to_string(&package_path) {
+                let package_data = serde_json::from_str::<serde_json::Value>(&package_json)
+                    .map_err(|e| ConfigError::from_json(&package_path, e))?;
                 if let Some(config_data) = package_data.get("biome-tailwind-sorter") {
-                    if let Ok(config) = serde_json::from_value::<Config>(config_data.clone()) {
-                        return config;
-                    }
+                    let config = serde_json::from_value::<Config>(config_data.clone()).map_err(|e| {
+                        ConfigError::new(&package_path, format!("\"biome-tailwind-sorter\": {e}"))
+                    })?;
+                    config.validate_globs().map_err(|(_, message)| {
+                        ConfigError::new(&package_path, format!("\"biome-tailwind-sorter\": {message}"))
+                    })?;
+                    let value = Self::resolve_extends(config_data.clone(), &package_path, &mut Vec::new())?;
+                    explicit = Some((value, package_path));
                 }
             }
         }
         
-        Config::default()
+        let (value, source) = match (biome, explicit) {
+            (Some((mut base, _)), Some((explicit, path))) => {
+                merge_values(&mut base, explicit);
+                (base, path)
+            }
+            (Some((base, path)), None) => (base, path),
+            (None, Some((explicit, path))) => (explicit, path),
+            (None, None) => return Ok(None),
+        };
+        ConfigLayer::new(dir.to_path_buf(), source, value).map(Some)
     }
     
-    /// Save configuration to a file
-    #[allow(dead_code)]
+    /// Load a config file as a raw value, after checking that it parses
+    pub fn load_value(path: &Path) -> Result<serde_json::Value, ConfigError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| ConfigError::new(path, format!("cannot read file: {e}")))?;
+        Self::parse(path, &content)?;
+        
+        let value = match ConfigFormat::from_path(path) {
+            ConfigFormat::Json => serde_json::from_str(&content)
+                .map_err(|e| ConfigError::from_json(path, e))?,
+            ConfigFormat::Jsonc => serde_json::from_str(&strip_jsonc(&content))
+                .map_err(|e| ConfigError::from_json(path, e))?,
+            ConfigFormat::Toml => toml::from_str(&content)
+                .map_err(|e| ConfigError::from_toml(path, &content, e))?,
+        };
+        Ok(value)
+    }
+    
+    /// Load a config file as a raw value with its `extends` chain merged in
+    fn load_extended_value(path: &Path, seen: &mut Vec<PathBuf>) -> Result<serde_json::Value, ConfigError> {
+        let value = Self::load_value(path)?;
+        Self::resolve_extends(value, path, seen)
+    }
+    
+    /// Merge `value`, loaded from `path`, over the files it extends. The
+    /// `overrides` of all files are kept, outermost first.
+    fn resolve_extends(
+        mut value: serde_json::Value,
+        path: &Path,
+        seen: &mut Vec<PathBuf>,
+    ) -> Result<serde_json::Value, ConfigError> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if seen.contains(&canonical) {
+            return Err(ConfigError::new(path, "circular \"extends\""));
+        }
+        
+        let Some(object) = value.as_object_mut() else {
+            return Ok(value);
+        };
+        let extends = match object.remove("extends") {
+            Some(serde_json::Value::String(parent)) => vec![parent],
+            Some(serde_json::Value::Array(parents)) => parents
+                .into_iter()
+                .filter_map(|parent| parent.as_str().map(String::from))
+                .collect(),
+            _ => Vec::new(),
+        };
+        if extends.is_empty() {
+            return Ok(value);
+        }
+        
+        seen.push(canonical);
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let mut base = serde_json::Value::Object(serde_json::Map::new());
+        let mut overrides = Vec::new();
+        for parent in extends {
+            let mut parent_value = Self::load_extended_value(&dir.join(&parent), seen)?;
+            take_overrides(&mut parent_value, &mut overrides);
+            merge_values(&mut base, parent_value);
+        }
+        seen.pop();
+        
+        take_overrides(&mut value, &mut overrides);
+        merge_values(&mut base, value);
+        if !overrides.is_empty() {
+            if let Some(object) = base.as_object_mut() {
+                object.insert("overrides".into(), overrides.into());
+            }
+        }
+        Ok(base)
+    }
+    
+    /// Save configuration to a file, as TOML for `.toml` paths and JSON otherwise
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
-        let content = serde_json::to_string_pretty(self)?;
+        let content = match ConfigFormat::from_path(path.as_ref()) {
+            ConfigFormat::Toml => toml::to_string_pretty(self)?,
+            ConfigFormat::Json | ConfigFormat::Jsonc => serde_json::to_string_pretty(self)? + "\n",
+        };
         fs::write(path, content)?;
         Ok(())
     }
     
-    /// Check if a file should be processed based on extension
-    #[allow(dead_code)]
+    /// Check if a file should be processed based on extension and the
+    /// `include` patterns
     pub fn should_process_file(&self, file_path: &str) -> bool {
-        if let Some(extension) = Path::new(file_path).extension() {
-            if let Some(ext_str) = extension.to_str() {
-                return self.extensions.iter().any(|ext| ext == ext_str);
-            }
-        }
-        false
+        let has_extension = Path::new(file_path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|ext_str| self.extensions.iter().any(|ext| ext == ext_str));
+        
+        has_extension && self.is_included(file_path)
     }
     
-    /// Check if a path should be ignored
-    #[allow(dead_code)]
+    /// Check if a path matches the `include` patterns, if any are set
+    pub fn is_included(&self, file_path: &str) -> bool {
+        self.include.is_empty() || self.include_patterns().is_match(Path::new(file_path))
+    }
+    
+    /// Check if a path matches the `ignore` patterns
     pub fn should_ignore_path(&self, path: &str) -> bool {
-        self.ignore.iter().any(|ignore_pattern| {
-            // Simple pattern matching - could be enhanced with glob patterns
-            path.contains(ignore_pattern)
-        })
+        self.ignore_patterns().is_match(Path::new(path))
+    }
+    
+    /// Add `include` and `ignore` patterns relative to the working
+    /// directory, e.g. from the command line, keeping the base directory of
+    /// the existing ones
+    pub fn extend_patterns(&mut self, include: &[String], ignore: &[String]) {
+        let mut patterns = ConfigPatterns {
+            include: self.include_patterns().into_owned(),
+            ignore: self.ignore_patterns().into_owned(),
+        };
+        patterns.include.extend(PathPatterns::new(include));
+        patterns.ignore.extend(PathPatterns::new(ignore));
+        self.include.extend_from_slice(include);
+        self.ignore.extend_from_slice(ignore);
+        self.patterns = Some(patterns);
+    }
+    
+    /// The compiled `include` patterns, compiling them if they are missing
+    /// or out of date
+    pub fn include_patterns(&self) -> Cow<'_, PathPatterns> {
+        match &self.patterns {
+            Some(patterns) if patterns.include.sources == self.include => Cow::Borrowed(&patterns.include),
+            _ => Cow::Owned(PathPatterns::new(&self.include)),
+        }
+    }
+    
+    /// The compiled `ignore` patterns, compiling them if they are missing
+    /// or out of date
+    pub fn ignore_patterns(&self) -> Cow<'_, PathPatterns> {
+        match &self.patterns {
+            Some(patterns) if patterns.ignore.sources == self.ignore => Cow::Borrowed(&patterns.ignore),
+            _ => Cow::Owned(PathPatterns::new(&self.ignore)),
+        }
     }
     
     /// Get the custom class order if defined
@@ -215,6 +1099,133 @@ mod tests {
         assert_eq!(config.custom_classes, vec!["my-custom-class"]);
     }
 
+    #[test]
+    fn test_load_multiline_layout() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, r#"{{ "multiline_layout": "group-per-line", "print_width": 100 }}"#).unwrap();
+        
+        let config = Config::load_from_file(temp_file.path()).unwrap();
+        assert_eq!(config.multiline_layout, MultilineLayout::GroupPerLine);
+        assert_eq!(config.print_width, 100);
+    }
+
+    #[test]
+    fn test_load_toml_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("tailwindsorter.toml");
+        fs::write(
+            &path,
+            r#"
+extensions = ["vue"]
+remove_duplicates = true
+custom_order = ["layout", "typography"]
+"#,
+        ).unwrap();
+        
+        let config = Config::load_from_file(&path).unwrap();
+        assert_eq!(config.extensions, vec!["vue"]);
+        assert!(config.remove_duplicates);
+        assert_eq!(config.get_custom_order().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_load_jsonc_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join(".tailwindsorterrc.jsonc");
+        fs::write(
+            &path,
+            r#"{
+  // Only Vue files
+  "extensions": ["vue", /* and */ "html",],
+  "ignore": ["https://example.com//not-a-comment"],
+}"#,
+        ).unwrap();
+        
+        let config = Config::load_from_file(&path).unwrap();
+        assert_eq!(config.extensions, vec!["vue", "html"]);
+        assert_eq!(config.ignore, vec!["https://example.com//not-a-comment"]);
+    }
+
+    #[test]
+    fn test_parse_errors_have_locations() {
+        let err = Config::parse(Path::new("config.json"), "{\n  \"recursive\": yes\n}").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(16)));
+        assert!(err.to_string().starts_with("config.json:2:16: "));
+        
+        let err = Config::parse(Path::new("config.jsonc"), "{\n  // comment\n  \"print_width\": \"wide\",\n}").unwrap_err();
+        assert_eq!(err.line, Some(3));
+        
+        let err = Config::parse(Path::new("tailwindsorter.toml"), "recursive = true\nprint_width = \"wide\"\n").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(15)));
+    }
+
+    #[test]
+    fn test_save_and_reload_toml() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("tailwindsorter.toml");
+        let config = Config {
+            custom_classes: vec!["btn".to_string()],
+            ..Config::default()
+        };
+        config.save_to_file(&path).unwrap();
+        
+        let reloaded = Config::load_from_file(&path).unwrap();
+        assert_eq!(reloaded.custom_classes, vec!["btn"]);
+        assert_eq!(reloaded.extensions, config.extensions);
+    }
+
+    #[test]
+    fn test_load_biome_settings() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("biome.json"),
+            r#"{
+  "files": { "ignore": ["legacy/**"], "include": ["src/**"] },
+  "linter": {
+    "rules": {
+      "nursery": {
+        "useSortedClasses": {
+          "level": "warn",
+          "options": { "attributes": ["classList"], "functions": ["clsx", "cn"] }
+        }
+      }
+    }
+  }
+}"#,
+        ).unwrap();
+        
+        let (value, path) = load_biome_settings(temp_dir.path()).unwrap().unwrap();
+        assert!(path.ends_with("biome.json"));
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(config.attributes, vec!["classList"]);
+        assert_eq!(config.functions, vec!["clsx", "cn"]);
+        assert_eq!(config.include, vec!["src/**"]);
+        assert!(config.ignore.contains(&"legacy/**".to_string()));
+        assert!(config.ignore.contains(&"node_modules".to_string()));
+    }
+
+    #[test]
+    fn test_explicit_config_overrides_biome_settings() {
+        let mut base = serde_json::json!({ "functions": ["clsx"], "attributes": ["classList"] });
+        merge_values(&mut base, serde_json::json!({ "functions": ["tw"], "recursive": false }));
+        
+        let config: Config = serde_json::from_value(base).unwrap();
+        assert_eq!(config.functions, vec!["tw"]);
+        assert_eq!(config.attributes, vec!["classList"]);
+        assert!(!config.recursive);
+    }
+
+    #[test]
+    fn test_include_patterns() {
+        let config = Config {
+            include: vec!["src/**/*.tsx".to_string()],
+            ..Config::default()
+        };
+        assert!(config.should_process_file("src/components/Button.tsx"));
+        assert!(config.should_process_file("./src/App.tsx"));
+        assert!(!config.should_process_file("scripts/build.tsx"));
+    }
+
     #[test]
     fn test_should_process_file() {
         let config = Config::default();
@@ -230,9 +1241,32 @@ mod tests {
         assert!(config.should_ignore_path("node_modules/package/file.js"));
         assert!(config.should_ignore_path("dist/bundle.js"));
         assert!(!config.should_ignore_path("src/components/Button.tsx"));
+        
+        // Bare names match whole path components only
+        assert!(config.should_ignore_path("src/build/app.tsx"));
+        assert!(!config.should_ignore_path("src/builder/app.tsx"));
+        
+        let config = Config {
+            ignore: vec!["*.stories.tsx".to_string(), "packages/legacy".to_string(), "src/*/gen/**".to_string()],
+            ..Config::default()
+        };
+        assert!(config.should_ignore_path("src/Button.stories.tsx"));
+        assert!(config.should_ignore_path("./packages/legacy/src/App.tsx"));
+        assert!(!config.should_ignore_path("apps/packages/legacy/App.tsx"));
+        assert!(config.should_ignore_path("src/ui/gen/icons.tsx"));
+        assert!(!config.should_ignore_path("src/ui/nested/gen/icons.tsx"));
+
+        // Patterns compiled before the list changed are not used
+        let mut config = config;
+        config.extend_patterns(&[], &["*.mdx".to_string()]);
+        assert!(matches!(config.ignore_patterns(), Cow::Borrowed(_)));
+        assert!(config.should_ignore_path("docs/intro.mdx"));
+        config.ignore.push("legacy".to_string());
+        assert!(config.should_ignore_path("apps/legacy/App.tsx"));
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_is_custom_class() {
         let mut config = Config::default();
         config.custom_classes = vec!["my-custom".to_string(), "another-custom".to_string()];
@@ -241,4 +1275,156 @@ mod tests {
         assert!(config.is_custom_class("another-custom"));
         assert!(!config.is_custom_class("text-red-500"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_resolver_nearest_config_wins() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let web = root.join("apps/web/src");
+        fs::create_dir_all(&web).unwrap();
+        fs::create_dir_all(root.join("apps/admin")).unwrap();
+        fs::write(
+            root.join("tailwindsorter.config.json"),
+            r#"{ "custom_classes": ["btn"], "remove_duplicates": true }"#,
+        ).unwrap();
+        fs::write(
+            root.join("apps/web/tailwindsorter.toml"),
+            "custom_classes = [\"card\"]\n",
+        ).unwrap();
+        
+        let mut resolver = ConfigResolver::new();
+        let web_config = resolver.resolve(&web.join("App.tsx")).unwrap();
+        assert_eq!(web_config.custom_classes, vec!["card"]);
+        assert!(web_config.remove_duplicates);
+        
+        let admin_config = resolver.resolve(&root.join("apps/admin/App.tsx")).unwrap();
+        assert_eq!(admin_config.custom_classes, vec!["btn"]);
+        
+        // Files sharing a config share the same instance
+        let other = resolver.resolve(&web.join("Other.tsx")).unwrap();
+        assert!(Rc::ptr_eq(&web_config, &other));
+        
+        let (_, source) = resolver.resolve_dir(&web).unwrap();
+        assert!(source.unwrap().ends_with("apps/web/tailwindsorter.toml"));
+    }
+
+    #[test]
+    fn test_path_patterns_are_relative_to_their_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        for app in ["apps/web", "apps/admin"] {
+            fs::create_dir_all(root.join(app)).unwrap();
+            fs::write(
+                root.join(app).join("tailwindsorter.config.json"),
+                r#"{ "include": ["src/**"], "ignore": ["src/legacy/**"] }"#,
+            ).unwrap();
+        }
+        
+        let mut resolver = ConfigResolver::new();
+        for app in ["apps/web", "apps/admin"] {
+            let file = root.join(app).join("src/App.tsx");
+            let config = resolver.resolve(&file).unwrap();
+            assert!(config.should_process_file(&file.to_string_lossy()), "{app}");
+            let legacy = root.join(app).join("src/legacy/App.tsx").to_string_lossy().to_string();
+            assert!(config.should_ignore_path(&legacy), "{app}");
+            assert!(!config.should_process_file(&root.join(app).join("scripts/a.ts").to_string_lossy()));
+        }
+    }
+
+    #[test]
+    fn test_extends_and_overrides() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("shared")).unwrap();
+        fs::create_dir_all(root.join("packages/legacy")).unwrap();
+        fs::write(
+            root.join("shared/base.json"),
+            r#"{ "custom_classes": ["btn"], "print_width": 100 }"#,
+        ).unwrap();
+        fs::write(
+            root.join(".tailwindsorterrc.jsonc"),
+            r#"{
+  "extends": "./shared/base.json",
+  "print_width": 120,
+  "overrides": [
+    { "files": ["packages/legacy/**"], "sort_custom_classes": true, "custom_classes": ["old"] },
+    { "files": ["src/ui/*.tsx", "*.stories.tsx"], "print_width": 80 }
+  ]
+}"#,
+        ).unwrap();
+        
+        let mut resolver = ConfigResolver::new();
+        let config = resolver.resolve(&root.join("src/App.tsx")).unwrap();
+        assert_eq!(config.custom_classes, vec!["btn"]);
+        assert_eq!(config.print_width, 120);
+        assert!(!config.sort_custom_classes);
+        
+        let legacy = resolver.resolve(&root.join("packages/legacy/App.tsx")).unwrap();
+        assert_eq!(legacy.custom_classes, vec!["old"]);
+        assert_eq!(legacy.print_width, 120);
+        assert!(legacy.sort_custom_classes);
+        
+        // Same rules as `ignore`: `*` stays in a directory, bare names match anywhere
+        assert_eq!(resolver.resolve(&root.join("src/ui/Card.tsx")).unwrap().print_width, 80);
+        assert_eq!(resolver.resolve(&root.join("src/ui/kit/Card.tsx")).unwrap().print_width, 120);
+        assert_eq!(resolver.resolve(&root.join("src/Card.stories.tsx")).unwrap().print_width, 80);
+    }
+
+    #[test]
+    fn test_extends_cycle_is_an_error() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("a.json");
+        fs::write(&path, r#"{ "extends": "b.json" }"#).unwrap();
+        fs::write(temp_dir.path().join("b.json"), r#"{ "extends": ["a.json"] }"#).unwrap();
+        
+        let err = Config::load_from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("circular"), "{err}");
+    }
+
+    #[test]
+    fn test_unknown_keys_suggest_names() {
+        let err = Config::parse(Path::new("config.json"), "{\n  \"extentions\": [\"vue\"]\n}").unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert!(err.message.ends_with("unknown field `extentions`, did you mean `extensions`?"), "{err}");
+        
+        let err = Config::parse(Path::new("tailwindsorter.toml"), "multiline_layout = \"one_per_line\"\n").unwrap_err();
+        assert!(err.message.ends_with("unknown value `one_per_line`, did you mean `one-per-line`?"), "{err}");
+        
+        // Without a close match the expected names are listed
+        let err = Config::parse(Path::new("config.json"), r#"{ "colour": true }"#).unwrap_err();
+        assert!(err.message.contains("expected one of"), "{err}");
+        
+        let json = r#"{ "$schema": "./tailwindsorter.schema.json", "recursive": false }"#;
+        assert!(Config::parse(Path::new("config.json"), json).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_globs_are_errors() {
+        let content = "{\n  \"include\": [\"src/**\", \"src/{a,b\"]\n}";
+        let err = Config::parse(Path::new("config.json"), content).unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(25)));
+        assert!(err.message.starts_with("invalid glob `src/{a,b`"), "{err}");
+        
+        let content = r#"{ "overrides": [{ "files": ["[z-a]"], "recursive": false }] }"#;
+        assert!(Config::parse(Path::new("config.json"), content).is_err());
+        
+        let content = "{\n  \"ignore\": [\"dist\", \"src/[abc\"]\n}";
+        let err = Config::parse(Path::new("config.json"), content).unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(22)));
+        assert!(err.message.starts_with("invalid glob `src/[abc`"), "{err}");
+        
+        let err = ConfigLayer::new(
+            PathBuf::from("."),
+            PathBuf::from("config.json"),
+            serde_json::json!({ "overrides": [{ "files": ["*.vue"], "print_widht": 100 }] }),
+        ).unwrap_err();
+        assert!(err.message.contains("did you mean `print_width`?"), "{err}");
+    }
+
+    #[test]
+    fn test_published_schema_is_up_to_date() {
+        let published: serde_json::Value =
+            serde_json::from_str(include_str!("../tailwindsorter.schema.json")).unwrap();
+        assert_eq!(published, Config::json_schema(), "run `biome-tailwind-sorter print-schema > tailwindsorter.schema.json`");
+    }
+}
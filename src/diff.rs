@@ -0,0 +1,101 @@
+use similar::{ChangeTag, TextDiff};
+use std::fmt::Write;
+use std::io::IsTerminal;
+
+/// Lines of context around each changed line; class attributes are usually
+/// on one line, so a single line is enough to locate them
+const CONTEXT_LINES: usize = 1;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const BOLD: &str = "\x1b[1m";
+const UNDERLINE: &str = "\x1b[4m";
+const RESET: &str = "\x1b[0m";
+
+/// Whether output written to stdout should be colored: only on a terminal,
+/// and not when `NO_COLOR` is set
+pub fn use_color() -> bool {
+    std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal()
+}
+
+/// Unified diff between the original and formatted source of a file.
+///
+/// With `color`, removed lines are red, added lines green, and the parts of
+/// a line that changed (the classes that moved) are underlined.
+pub fn unified_diff(path: &str, before: &str, after: &str, color: bool) -> String {
+    let diff = TextDiff::from_lines(before, after);
+    let mut output = String::new();
+    let paint = |code: &str, text: &str| {
+        if color {
+            format!("{code}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    };
+    
+    let _ = writeln!(output, "{}", paint(BOLD, &format!("--- a/{path}")));
+    let _ = writeln!(output, "{}", paint(BOLD, &format!("+++ b/{path}")));
+    
+    let mut unified = diff.unified_diff();
+    unified.context_radius(CONTEXT_LINES);
+    for hunk in unified.iter_hunks() {
+        let _ = writeln!(output, "{}", paint(CYAN, &hunk.header().to_string()));
+        
+        for op in hunk.ops() {
+            for change in diff.iter_inline_changes(op) {
+                let (sign, line_color) = match change.tag() {
+                    ChangeTag::Delete => ("-", RED),
+                    ChangeTag::Insert => ("+", GREEN),
+                    ChangeTag::Equal => (" ", ""),
+                };
+                
+                let mut line = String::from(sign);
+                for (emphasized, text) in change.iter_strings_lossy() {
+                    let text = text.trim_end_matches(['\n', '\r']);
+                    if color && emphasized {
+                        let _ = write!(line, "{UNDERLINE}{text}{RESET}{line_color}");
+                    } else {
+                        line.push_str(text);
+                    }
+                }
+                
+                if color && !line_color.is_empty() {
+                    let _ = writeln!(output, "{line_color}{line}{RESET}");
+                } else {
+                    let _ = writeln!(output, "{line}");
+                }
+                if change.missing_newline() {
+                    let _ = writeln!(output, "\\ No newline at end of file");
+                }
+            }
+        }
+    }
+    
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff() {
+        let before = "<main>\n  <div class=\"p-4 flex\">\n    text\n  </div>\n</main>\n";
+        let after = "<main>\n  <div class=\"flex p-4\">\n    text\n  </div>\n</main>\n";
+        
+        assert_eq!(
+            unified_diff("index.html", before, after, false),
+            "--- a/index.html\n+++ b/index.html\n@@ -1,3 +1,3 @@\n <main>\n-  <div class=\"p-4 flex\">\n+  <div class=\"flex p-4\">\n     text\n"
+        );
+        
+        let colored = unified_diff("index.html", before, after, true);
+        assert!(colored.contains(&format!("{RED}-  <div {UNDERLINE}class=\"p-4{RESET}")));
+    }
+
+    #[test]
+    fn test_unified_diff_without_trailing_newline() {
+        let diff = unified_diff("a.html", "<a class=\"p-4 flex\">", "<a class=\"flex p-4\">", false);
+        assert!(diff.ends_with("+<a class=\"flex p-4\">\n\\ No newline at end of file\n"), "{diff}");
+    }
+}
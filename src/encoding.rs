@@ -0,0 +1,112 @@
+use crate::error::SorterError;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use std::path::Path;
+
+/// Decoded source of a file, remembering how to write it back byte for byte
+#[derive(Debug, Clone)]
+pub struct SourceText {
+    /// The text without a byte order mark
+    pub text: String,
+    pub encoding: &'static Encoding,
+    /// Whether the source started with a byte order mark
+    pub bom: bool,
+}
+
+impl SourceText {
+    /// Decode `bytes` read from `path`. A byte order mark selects UTF-8 or
+    /// UTF-16 regardless of `encoding`.
+    pub fn decode(path: &Path, bytes: Vec<u8>, encoding: &'static Encoding) -> Result<Self, SorterError> {
+        let (encoding, bom_length) = Encoding::for_bom(&bytes).unwrap_or((encoding, 0));
+        let bom = bom_length > 0;
+
+        let text = if encoding == UTF_8 {
+            let mut bytes = bytes;
+            bytes.drain(..bom_length);
+            String::from_utf8(bytes).map_err(|_| SorterError::NotUtf8 { path: path.to_path_buf() })?
+        } else {
+            encoding
+                .decode_without_bom_handling_and_without_replacement(&bytes[bom_length..])
+                .ok_or_else(|| SorterError::InvalidEncoding { path: path.to_path_buf(), encoding: encoding.name() })?
+                .into_owned()
+        };
+        Ok(Self { text, encoding, bom })
+    }
+
+    /// Byte offset in the file of `offset` in `text`. Only UTF-8 files have
+    /// one; in other encodings characters take a different number of bytes.
+    pub fn file_offset(&self, offset: usize) -> Option<usize> {
+        (self.encoding == UTF_8).then(|| if self.bom { offset + 3 } else { offset })
+    }
+
+    /// Offset in `text` of byte `offset` of a UTF-8 file. Offsets into files
+    /// in other encodings can't be mapped and are taken to be offsets into
+    /// `text`.
+    pub fn text_offset(&self, offset: usize) -> usize {
+        if self.encoding == UTF_8 && self.bom {
+            offset.saturating_sub(3)
+        } else {
+            offset
+        }
+    }
+
+    /// Encode `text` (e.g. the formatted text) like the decoded source, with
+    /// its byte order mark
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(text.len() + 3);
+        // encoding_rs only encodes to UTF-8 for the UTF-16 encodings
+        if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+            let little_endian = self.encoding == UTF_16LE;
+            let units = self.bom.then_some(0xFEFF).into_iter().chain(text.encode_utf16());
+            for unit in units {
+                bytes.extend(if little_endian { unit.to_le_bytes() } else { unit.to_be_bytes() });
+            }
+            return bytes;
+        }
+
+        if self.bom {
+            bytes.extend_from_slice(b"\xEF\xBB\xBF");
+        }
+        bytes.extend_from_slice(&self.encoding.encode(text).0);
+        bytes
+    }
+}
+
+/// Look up an encoding by a WHATWG label such as `utf-8`, `latin1` or
+/// `shift_jis`
+pub fn parse_encoding(label: &str) -> Result<&'static Encoding, String> {
+    Encoding::for_label(label.trim().as_bytes()).ok_or_else(|| format!("unknown encoding `{label}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_with_bom() {
+        let path = Path::new("a.html");
+        let source = SourceText::decode(path, b"\xEF\xBB\xBF<a class=\"flex\">".to_vec(), UTF_8).unwrap();
+        assert!(source.bom);
+        assert_eq!(source.text, "<a class=\"flex\">");
+        assert_eq!(source.encode(&source.text), b"\xEF\xBB\xBF<a class=\"flex\">");
+        assert_eq!((source.file_offset(3), source.text_offset(6)), (Some(6), 3));
+
+        // The BOM takes precedence over the requested encoding
+        let bytes: Vec<u8> = b"\xFF\xFE".iter().copied().chain("<a>é".encode_utf16().flat_map(u16::to_le_bytes)).collect();
+        let source = SourceText::decode(path, bytes.clone(), UTF_8).unwrap();
+        assert_eq!((source.text.as_str(), source.encoding), ("<a>é", UTF_16LE));
+        assert_eq!(source.encode(&source.text), bytes);
+        assert_eq!((source.file_offset(3), source.text_offset(3)), (None, 3));
+    }
+
+    #[test]
+    fn test_legacy_encoding_round_trips_every_byte() {
+        let latin1 = parse_encoding("latin1").unwrap();
+        let bytes: Vec<u8> = (0..=255).collect();
+        let source = SourceText::decode(Path::new("a.html"), bytes.clone(), latin1).unwrap();
+        assert_eq!(source.encode(&source.text), bytes);
+
+        let err = SourceText::decode(Path::new("a.html"), b"caf\xE9".to_vec(), UTF_8).unwrap_err();
+        assert_eq!(err.render(), "'a.html' is not valid UTF-8");
+        assert!(parse_encoding("klingon").is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::config::ConfigError;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Why a file could not be processed
+#[derive(Debug)]
+pub enum SorterError {
+    /// Accessing, reading or writing a file failed
+    Io {
+        path: PathBuf,
+        /// What was attempted, e.g. `read`
+        action: &'static str,
+        source: io::Error,
+    },
+    /// A file is larger than `max_file_size`
+    TooLarge { path: PathBuf, size: u64, max_size: u64 },
+    /// A file is not valid UTF-8
+    NotUtf8 { path: PathBuf },
+    /// A file is not valid in the encoding given with `--encoding`
+    InvalidEncoding { path: PathBuf, encoding: &'static str },
+    /// A configuration file could not be loaded
+    Config(ConfigError),
+    /// A file could not be parsed, at a 1-based line and column. The
+    /// bundled extractors are lenient and never fail, so only other
+    /// parsers built on the library report it.
+    #[allow(dead_code)]
+    Parse {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// Running git for `--staged` or `--since` failed
+    Git(String),
+}
+
+impl SorterError {
+    pub fn io(path: impl Into<PathBuf>, action: &'static str, source: io::Error) -> Self {
+        Self::Io { path: path.into(), action, source }
+    }
+
+    /// Exit code of the CLI for this error; 1 is reserved for files needing
+    /// formatting
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Io { .. } => 2,
+            Self::Config(_) => 3,
+            Self::TooLarge { .. } => 4,
+            Self::NotUtf8 { .. } | Self::InvalidEncoding { .. } => 5,
+            Self::Parse { .. } => 6,
+            Self::Git(_) => 7,
+        }
+    }
+
+    /// The error and its causes on one line
+    pub fn render(&self) -> String {
+        let mut rendered = self.to_string();
+        let mut source = self.source();
+        while let Some(cause) = source {
+            rendered.push_str(&format!(": {cause}"));
+            source = cause.source();
+        }
+        rendered
+    }
+}
+
+impl fmt::Display for SorterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, action, .. } => write!(f, "Failed to {action} '{}'", path.display()),
+            Self::TooLarge { path, size, max_size } => write!(
+                f,
+                "'{}' is too large ({size} bytes). Maximum size is {max_size} bytes.",
+                path.display()
+            ),
+            Self::NotUtf8 { path } => write!(f, "'{}' is not valid UTF-8", path.display()),
+            Self::InvalidEncoding { path, encoding } => {
+                write!(f, "'{}' is not valid {encoding}", path.display())
+            }
+            Self::Config(err) => write!(f, "Failed to load config: {err}"),
+            Self::Parse { path, line, column, message } => {
+                write!(f, "{}:{line}:{column}: {message}", path.display())
+            }
+            Self::Git(message) => f.write_str(message),
+        }
+    }
+}
+
+impl Error for SorterError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<ConfigError> for SorterError {
+    fn from(err: ConfigError) -> Self {
+        Self::Config(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_and_exit_codes() {
+        let err = SorterError::io(
+            "missing.html",
+            "read",
+            io::Error::new(io::ErrorKind::NotFound, "No such file"),
+        );
+        assert_eq!(err.render(), "Failed to read 'missing.html': No such file");
+        assert_eq!(err.exit_code(), 2);
+
+        let err = SorterError::TooLarge { path: "big.html".into(), size: 20, max_size: 10 };
+        assert_eq!(err.render(), "'big.html' is too large (20 bytes). Maximum size is 10 bytes.");
+        assert_eq!(err.exit_code(), 4);
+
+        let err = SorterError::Parse { path: "a.vue".into(), line: 2, column: 5, message: "unclosed tag".to_string() };
+        assert_eq!(err.render(), "a.vue:2:5: unclosed tag");
+        assert_eq!(err.exit_code(), 6);
+    }
+}
@@ -1,8 +1,15 @@
 use crate::class_extractor::{
-    extract_class_names, reconstruct_class_string, contains_tailwind_classes,
+    extract_class_names, layout_class_string, normalize_class_whitespace,
+    remove_duplicate_classes, MultilineLayout,
 };
-use crate::tailwind_order::sort_tailwind_classes;
+use crate::config::Config;
+use crate::lint::{
+    collapse_shorthands, find_conflicts, find_shorthands, LintDiagnostic, LintKind,
+};
+use crate::tailwind_order::Sorter;
 use regex::Regex;
+use serde::Serialize;
+use std::ops::Range;
 use std::sync::LazyLock;
 
 #[derive(Debug, Clone)]
@@ -17,6 +24,109 @@ pub struct FormatResult {
     pub content: String,
     pub cursor_position: Option<CursorPosition>,
     pub changed: bool,
+    /// Number of duplicate classes dropped (only with `remove_duplicates`)
+    pub duplicates_removed: usize,
+    /// Number of class pairs collapsed into shorthands (only with `fix_shorthands`)
+    pub shorthands_collapsed: usize,
+    /// Rewritten class attributes, in document order
+    pub changes: Vec<AttributeChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// The class list changed (order, duplicates or shorthands)
+    Sorted,
+    /// Only the whitespace between classes changed
+    Whitespace,
+}
+
+/// A class attribute rewritten by the formatter
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct AttributeChange {
+    /// Byte offset of the attribute in the original document
+    pub start: usize,
+    /// Byte offset just past the attribute in the original document
+    pub end: usize,
+    /// 0-based line of the attribute in the original document
+    pub line: usize,
+    /// 0-based column (in bytes) of the attribute in the original document
+    pub column: usize,
+    /// 0-based line of the end of the attribute in the original document
+    pub end_line: usize,
+    /// 0-based column (in bytes) just past the attribute in the original document
+    pub end_column: usize,
+    pub kind: ChangeKind,
+    /// Class string before formatting
+    pub before: String,
+    /// Class string after formatting
+    pub after: String,
+}
+
+/// A replacement of `source[start..end]` with `text`, in byte offsets of the
+/// original document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TextEdit {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+impl TextEdit {
+    /// Apply non-overlapping edits, given in document order, to `source`
+    #[allow(dead_code)]
+    pub fn apply(source: &str, edits: &[TextEdit]) -> String {
+        let mut result = source.to_string();
+        for edit in edits.iter().rev() {
+            result.replace_range(edit.start..edit.end, &edit.text);
+        }
+        result
+    }
+}
+
+/// Optional fixes and layout settings applied on top of sorting
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    /// Drop exact duplicate classes
+    pub remove_duplicates: bool,
+    /// Collapse pairs like `mt-2 mb-2` into shorthands like `my-2`
+    pub fix_shorthands: bool,
+    /// Collapse runs of whitespace and trim class attributes, even when
+    /// they are already sorted
+    pub normalize_whitespace: bool,
+    /// Keep multiline class attributes on multiple lines
+    pub preserve_multiline: bool,
+    /// How classes are laid out in multiline class attributes
+    pub layout: MultilineLayout,
+    /// Maximum line width for [`MultilineLayout::Wrap`]
+    pub print_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            remove_duplicates: false,
+            fix_shorthands: false,
+            normalize_whitespace: false,
+            preserve_multiline: true,
+            layout: MultilineLayout::default(),
+            print_width: 80,
+        }
+    }
+}
+
+impl FormatOptions {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            remove_duplicates: config.remove_duplicates,
+            fix_shorthands: config.fix_shorthands,
+            normalize_whitespace: config.normalize_whitespace,
+            preserve_multiline: config.preserve_multiline,
+            layout: config.multiline_layout,
+            print_width: config.print_width,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -30,16 +140,114 @@ pub struct ClassMatch {
     pub _line_end: usize,
 }
 
+struct ProcessedClasses {
+    classes: Vec<String>,
+    duplicates_removed: usize,
+    shorthands_collapsed: usize,
+}
+
 pub struct TailwindFormatter {
     preserve_cursor: bool,
+    sorter: Sorter,
+    options: FormatOptions,
+    /// Double and single quote regexes when extra attributes are configured
+    attribute_regexes: Option<(Regex, Regex)>,
+    /// Matches calls (or tagged templates) of the configured class functions
+    function_regex: Option<Regex>,
 }
 
 impl TailwindFormatter {
     pub fn new(preserve_cursor: bool) -> Self {
-        Self { preserve_cursor }
+        Self {
+            preserve_cursor,
+            sorter: Sorter::default(),
+            options: FormatOptions::default(),
+            attribute_regexes: None,
+            function_regex: None,
+        }
     }
 
-    pub fn format_document(&self, source: &str, cursor_pos: Option<CursorPosition>) -> FormatResult {
+    /// Formatter using the sorting, fix and matching settings of a config
+    pub fn from_config(preserve_cursor: bool, config: &Config) -> Self {
+        Self::new(preserve_cursor)
+            .with_sorter(Sorter::from_config(config))
+            .with_options(FormatOptions::from_config(config))
+            .with_matchers(&config.attributes, &config.functions)
+    }
+
+    /// Also sort classes in the given attributes (besides `class` and
+    /// `className`) and in string arguments of the given functions
+    pub fn with_matchers(mut self, attributes: &[String], functions: &[String]) -> Self {
+        self.attribute_regexes = if attributes.is_empty() {
+            None
+        } else {
+            let names: Vec<String> = ["class", "className"]
+                .iter()
+                .map(|name| name.to_string())
+                .chain(attributes.iter().map(|name| regex::escape(name)))
+                .collect();
+            let names = names.join("|");
+            Some((
+                Regex::new(&format!(r#"((?:{names})=")([^"]*?)""#)).unwrap(),
+                Regex::new(&format!(r#"((?:{names})=')([^']*?)'"#)).unwrap(),
+            ))
+        };
+        
+        self.function_regex = if functions.is_empty() {
+            None
+        } else {
+            let names: Vec<String> = functions.iter().map(|name| regex::escape(name)).collect();
+            Some(Regex::new(&format!(r"\b(?:{})\s*[(`]", names.join("|"))).unwrap())
+        };
+        self
+    }
+
+    /// Use a sorter built from a config instead of the default Tailwind order
+    pub fn with_sorter(mut self, sorter: Sorter) -> Self {
+        self.sorter = sorter;
+        self
+    }
+
+    pub fn with_options(mut self, options: FormatOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Apply the enabled fixes and sort
+    fn process_classes(&self, class_names: &[String]) -> ProcessedClasses {
+        let mut classes = class_names.to_vec();
+        let mut duplicates_removed = 0;
+        let mut shorthands_collapsed = 0;
+        
+        if self.options.remove_duplicates {
+            (classes, duplicates_removed) = remove_duplicate_classes(&classes);
+        }
+        if self.options.fix_shorthands {
+            (classes, shorthands_collapsed) = collapse_shorthands(&classes);
+        }
+        
+        ProcessedClasses {
+            classes: self.sorter.sort(&classes),
+            duplicates_removed,
+            shorthands_collapsed,
+        }
+    }
+
+    fn layout_classes(&self, class_names: &[String], original: &str, first_column: usize) -> String {
+        if !self.options.preserve_multiline {
+            return class_names.join(" ");
+        }
+        layout_class_string(
+            class_names,
+            original,
+            self.options.layout,
+            self.options.print_width,
+            first_column,
+        )
+    }
+
+    /// Find all class attributes in the document, ordered by position
+    pub fn find_class_matches(&self, source: &str) -> Vec<ClassMatch> {
         static DOUBLE_QUOTE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
             Regex::new(r#"(class(?:Name)?=")([^"]*?)""#).unwrap()
         });
@@ -47,12 +255,10 @@ impl TailwindFormatter {
             Regex::new(r#"(class(?:Name)?=')([^']*?)'"#).unwrap()
         });
         
-        let double_quote_regex = &*DOUBLE_QUOTE_REGEX;
-        let single_quote_regex = &*SINGLE_QUOTE_REGEX;
-        let mut result = source.to_string();
-        let mut offset_adjustment = 0i32;
-        let mut changed = false;
-        let mut new_cursor_pos = cursor_pos.clone();
+        let (double_quote_regex, single_quote_regex) = match &self.attribute_regexes {
+            Some((double, single)) => (double, single),
+            None => (&*DOUBLE_QUOTE_REGEX, &*SINGLE_QUOTE_REGEX),
+        };
 
         // Find all class matches and collect them
         let mut matches: Vec<ClassMatch> = Vec::new();
@@ -93,29 +299,168 @@ impl TailwindFormatter {
             }
         }
         
-        // Sort matches by start position
-        matches.sort_by(|a, b| a.start.cmp(&b.start));
+        // Find string literals passed to class functions like clsx(...)
+        if let Some(function_regex) = &self.function_regex {
+            for m in function_regex.find_iter(source) {
+                for (start, end) in self.function_string_literals(source, m.end() - 1) {
+                    let quote = &source[start..start + 1];
+                    matches.push(ClassMatch {
+                        start,
+                        end,
+                        prefix: quote.to_string(),
+                        classes: source[start + 1..end - 1].to_string(),
+                        suffix: quote.to_string(),
+                        _line_start: self.get_line_from_offset(source, start),
+                        _line_end: self.get_line_from_offset(source, end),
+                    });
+                }
+            }
+        }
+        
+        // Sort matches by start position, dropping any nested in an earlier match
+        matches.sort_by_key(|a| a.start);
+        let mut last_end = 0;
+        matches.retain(|m| {
+            let keep = m.start >= last_end;
+            if keep {
+                last_end = m.end;
+            }
+            keep
+        });
+        matches
+    }
+
+    /// Byte ranges (including quotes) of the string literals in a function
+    /// call or tagged template starting at `open`, which points at the `(`
+    /// or the opening backtick. Template literals with interpolations are
+    /// skipped.
+    fn function_string_literals(&self, source: &str, open: usize) -> Vec<(usize, usize)> {
+        let bytes = source.as_bytes();
+        let mut literals = Vec::new();
+        let mut depth = 0usize;
+        let mut i = open;
+        
+        while i < bytes.len() {
+            match bytes[i] {
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                quote @ (b'"' | b'\'' | b'`') => {
+                    let start = i;
+                    i += 1;
+                    while i < bytes.len() && bytes[i] != quote {
+                        if bytes[i] == b'\\' {
+                            i += 1;
+                        }
+                        i += 1;
+                    }
+                    let end = (i + 1).min(bytes.len());
+                    let content = &source[start + 1..end.saturating_sub(1).max(start + 1)];
+                    if i < bytes.len() && !content.contains("${") {
+                        literals.push((start, end));
+                    }
+                    // A tagged template is a single literal
+                    if start == open {
+                        break;
+                    }
+                }
+                b';' if depth == 0 => break,
+                _ => {}
+            }
+            i += 1;
+        }
+        
+        literals
+    }
+
+    pub fn format_document(&self, source: &str, cursor_pos: Option<CursorPosition>) -> FormatResult {
+        self.format_matches(source, cursor_pos, None).0
+    }
+
+    /// Format only the class attributes overlapping the byte range `range`
+    /// of `source`, leaving the rest of the document untouched
+    pub fn format_range(
+        &self,
+        source: &str,
+        range: Range<usize>,
+        cursor_pos: Option<CursorPosition>,
+    ) -> FormatResult {
+        self.format_matches(source, cursor_pos, Some(range)).0
+    }
+
+    /// The edits formatting the class attributes overlapping the byte range
+    /// `range`, in document order, without applying them
+    #[allow(dead_code)]
+    pub fn format_range_edits(&self, source: &str, range: Range<usize>) -> Vec<TextEdit> {
+        self.format_matches(source, None, Some(range)).1
+    }
+
+    /// Format the class attributes of `source`, or only those overlapping
+    /// `range`, returning the result and the edits making it
+    fn format_matches(
+        &self,
+        source: &str,
+        cursor_pos: Option<CursorPosition>,
+        range: Option<Range<usize>>,
+    ) -> (FormatResult, Vec<TextEdit>) {
+        let mut result = source.to_string();
+        let mut changed = false;
+        let mut duplicates_removed = 0;
+        let mut shorthands_collapsed = 0;
+        // Editors may send an offset inside a multibyte character
+        let mut new_cursor_pos = cursor_pos.map(|cursor| CursorPosition {
+            offset: floor_char_boundary(source, cursor.offset),
+            ..cursor
+        });
+        let mut changes = Vec::new();
+        let mut edits = Vec::new();
+        let matches = self.find_class_matches(source);
 
         // Process matches in reverse order to maintain correct offsets
         for class_match in matches.into_iter().rev() {
+            if range
+                .as_ref()
+                .is_some_and(|range| class_match.end <= range.start || class_match.start >= range.end)
+            {
+                continue;
+            }
+            
             let class_names = extract_class_names(&class_match.classes);
             
-            if !contains_tailwind_classes(&class_names) {
+            if !self.sorter.contains_tailwind_classes(&class_names) {
                 continue;
             }
 
-            let sorted_classes = sort_tailwind_classes(&class_names);
+            let processed = self.process_classes(&class_names);
+            let sorted_classes = processed.classes;
+            // Column the value starts at, for the wrap layout
+            let value_start = class_match.start + class_match.prefix.len();
+            let line_start = source[..value_start].rfind('\n').map_or(0, |i| i + 1);
+            let first_column = source[line_start..value_start].chars().count();
             
             // Check if sorting is needed
-            if class_names == sorted_classes {
+            let (kind, sorted_class_string) = if class_names != sorted_classes {
+                let layout = self.layout_classes(&sorted_classes, &class_match.classes, first_column);
+                (ChangeKind::Sorted, layout)
+            } else if self.options.normalize_whitespace {
+                let normalized = if self.options.layout == MultilineLayout::Preserve {
+                    normalize_class_whitespace(&class_match.classes)
+                } else {
+                    self.layout_classes(&sorted_classes, &class_match.classes, first_column)
+                };
+                if normalized == class_match.classes {
+                    continue;
+                }
+                (ChangeKind::Whitespace, normalized)
+            } else {
                 continue;
-            }
-
-            let sorted_class_string = reconstruct_class_string(
-                &sorted_classes,
-                &class_match.classes,
-                class_match.classes.contains('\n')
-            );
+            };
+            duplicates_removed += processed.duplicates_removed;
+            shorthands_collapsed += processed.shorthands_collapsed;
 
             let new_attribute = format!(
                 "{}{}{}",
@@ -124,11 +469,6 @@ impl TailwindFormatter {
                 class_match.suffix
             );
 
-            // Calculate the difference in length
-            let old_length = class_match.end - class_match.start;
-            let new_length = new_attribute.len();
-            let length_diff = new_length as i32 - old_length as i32;
-
             // Adjust cursor position if needed
             if let (Some(cursor), true) = (&mut new_cursor_pos, self.preserve_cursor) {
                 cursor.offset = self.adjust_cursor_offset(
@@ -137,32 +477,106 @@ impl TailwindFormatter {
                     class_match.end,
                     &class_match.classes,
                     &sorted_class_string,
-                    offset_adjustment,
                 );
             }
 
-            // Replace the text
-            let adjusted_start = (class_match.start as i32 + offset_adjustment) as usize;
-            let adjusted_end = (class_match.end as i32 + offset_adjustment) as usize;
-            
-            result.replace_range(adjusted_start..adjusted_end, &new_attribute);
+            // Replace the text. Matches are processed back to front, so the
+            // offsets of this match are unaffected by earlier replacements.
+            result.replace_range(class_match.start..class_match.end, &new_attribute);
+            edits.push(TextEdit {
+                start: class_match.start,
+                end: class_match.end,
+                text: new_attribute,
+            });
             
-            offset_adjustment += length_diff;
+            let (line, column) = self.get_line_column_from_offset(source, class_match.start);
+            let (end_line, end_column) = self.get_line_column_from_offset(source, class_match.end);
+            changes.push(AttributeChange {
+                start: class_match.start,
+                end: class_match.end,
+                line,
+                column,
+                end_line,
+                end_column,
+                kind,
+                before: class_match.classes.clone(),
+                after: sorted_class_string,
+            });
             changed = true;
         }
+        changes.reverse();
+        edits.reverse();
 
-        // Recalculate line and column from offset if cursor was adjusted
+        // Recalculate line and column from offset if cursor was adjusted.
+        // Classes change length, so the offset may land inside a character.
         if let Some(cursor) = &mut new_cursor_pos {
+            cursor.offset = floor_char_boundary(&result, cursor.offset);
             let (line, column) = self.get_line_column_from_offset(&result, cursor.offset);
             cursor.line = line;
             cursor.column = column;
         }
 
-        FormatResult {
+        let result = FormatResult {
             content: result,
             cursor_position: new_cursor_pos,
             changed,
+            duplicates_removed,
+            shorthands_collapsed,
+            changes,
+        };
+        (result, edits)
+    }
+
+    /// Report conflicting classes and collapsible shorthand pairs without
+    /// rewriting the document
+    pub fn lint_document(&self, source: &str) -> Vec<LintDiagnostic> {
+        let mut diagnostics = Vec::new();
+        
+        for class_match in self.find_class_matches(source) {
+            let class_names = extract_class_names(&class_match.classes);
+            if !self.sorter.contains_tailwind_classes(&class_names) {
+                continue;
+            }
+            
+            let offsets = class_offsets(&class_match.classes);
+            let content_start = class_match.start + class_match.prefix.len();
+            
+            for conflict in find_conflicts(&class_names) {
+                let offset = content_start + offsets[conflict.second];
+                let (line, column) = self.get_line_column_from_offset(source, offset);
+                diagnostics.push(LintDiagnostic {
+                    kind: LintKind::Conflict,
+                    line: line + 1,
+                    column: column + 1,
+                    offset,
+                    message: format!(
+                        "`{}` conflicts with `{}` (both set {})",
+                        class_names[conflict.second],
+                        class_names[conflict.first],
+                        conflict.property
+                    ),
+                });
+            }
+            
+            for shorthand in find_shorthands(&class_names) {
+                let offset = content_start + offsets[shorthand.first];
+                let (line, column) = self.get_line_column_from_offset(source, offset);
+                diagnostics.push(LintDiagnostic {
+                    kind: LintKind::Shorthand,
+                    line: line + 1,
+                    column: column + 1,
+                    offset,
+                    message: format!(
+                        "`{} {}` can be collapsed to `{}`",
+                        class_names[shorthand.first],
+                        class_names[shorthand.second],
+                        shorthand.replacement
+                    ),
+                });
+            }
         }
+        
+        diagnostics
     }
 
     fn adjust_cursor_offset(
@@ -172,24 +586,20 @@ impl TailwindFormatter {
         match_end: usize,
         original_classes: &str,
         sorted_classes: &str,
-        current_adjustment: i32,
     ) -> usize {
-        let adjusted_match_start = (match_start as i32 + current_adjustment) as usize;
-        let adjusted_match_end = (match_end as i32 + current_adjustment) as usize;
-
         // If cursor is before this match, no adjustment needed
-        if cursor_offset < adjusted_match_start {
+        if cursor_offset < match_start {
             return cursor_offset;
         }
 
         // If cursor is after this match, adjust by the length difference
-        if cursor_offset >= adjusted_match_end {
+        if cursor_offset >= match_end {
             let length_diff = sorted_classes.len() as i32 - original_classes.len() as i32;
             return (cursor_offset as i32 + length_diff).max(0) as usize;
         }
 
         // Cursor is within the match - try to preserve relative position
-        let relative_pos = cursor_offset - adjusted_match_start;
+        let relative_pos = cursor_offset - match_start;
         let class_content_start = original_classes.find(|c: char| !c.is_whitespace()).unwrap_or(0);
         
         // If cursor is in the prefix (class= part), keep it there
@@ -199,7 +609,7 @@ impl TailwindFormatter {
 
         // Try to map cursor position within the class content
         let original_class_names = extract_class_names(original_classes);
-        let sorted_class_names = sort_tailwind_classes(&original_class_names);
+        let sorted_class_names = self.process_classes(&original_class_names).classes;
         
         // Find which class the cursor was closest to
         let cursor_class_index = self.find_closest_class_index(
@@ -216,13 +626,13 @@ impl TailwindFormatter {
                     &sorted_class_names,
                     sorted_classes,
                 ) {
-                    return adjusted_match_start + class_content_start + new_pos;
+                    return match_start + class_content_start + new_pos;
                 }
             }
         }
 
         // Fallback: place cursor at the start of the sorted classes
-        adjusted_match_start + class_content_start
+        match_start + class_content_start
     }
 
     fn find_closest_class_index(
@@ -272,18 +682,52 @@ impl TailwindFormatter {
     }
 
     fn get_line_from_offset(&self, content: &str, offset: usize) -> usize {
-        content[..offset.min(content.len())].lines().count().saturating_sub(1)
+        self.get_line_column_from_offset(content, offset).0
     }
 
+    /// 0-based line and byte column of `offset`. Lines end at `\n`, so the
+    /// `\r` of a CRLF line ending is the last column of its line.
     fn get_line_column_from_offset(&self, content: &str, offset: usize) -> (usize, usize) {
-        let offset = offset.min(content.len());
-        let lines: Vec<&str> = content[..offset].lines().collect();
-        let line = lines.len().saturating_sub(1);
-        let column = lines.last().map(|l| l.len()).unwrap_or(0);
-        (line, column)
+        let before = &content[..floor_char_boundary(content, offset)];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        (before.matches('\n').count(), before.len() - line_start)
     }
 }
 
+/// Byte range of the 1-based lines `first..=last` of `source`, for
+/// [`TailwindFormatter::format_range`]
+pub fn line_range(source: &str, first: usize, last: usize) -> Range<usize> {
+    let line_start = |line: usize| {
+        if line <= 1 {
+            return 0;
+        }
+        source
+            .match_indices('\n')
+            .nth(line - 2)
+            .map_or(source.len(), |(index, _)| index + 1)
+    };
+    let start = line_start(first);
+    let end = line_start(last.max(first) + 1);
+    start..end.max(start)
+}
+
+/// The nearest char boundary of `source` at or before `offset`
+fn floor_char_boundary(source: &str, offset: usize) -> usize {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// Byte offsets of each whitespace-separated class within a class string
+fn class_offsets(class_string: &str) -> Vec<usize> {
+    class_string
+        .split_whitespace()
+        .map(|class| class.as_ptr() as usize - class_string.as_ptr() as usize)
+        .collect()
+}
+
 // Convenience function for basic formatting without cursor preservation
 #[allow(dead_code)]
 pub fn format_document(source: &str) -> String {
@@ -344,4 +788,207 @@ mod tests {
         let result = format_document(input);
         assert_eq!(input, result); // Should remain unchanged
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_format_with_config_sorter() {
+        let config = crate::config::Config {
+            custom_classes: vec!["card".to_string()],
+            ..crate::config::Config::default()
+        };
+        let formatter = TailwindFormatter::new(false).with_sorter(Sorter::from_config(&config));
+        let input = r#"<div class="card p-4 flex">content</div>"#;
+        let result = formatter.format_document(input, None);
+        assert!(result.content.contains(r#"class="flex p-4 card""#));
+    }
+
+    #[test]
+    fn test_remove_duplicates_option() {
+        let input = r#"<div class="p-4 flex p-4">content</div>"#;
+        let formatter = TailwindFormatter::new(false).with_options(FormatOptions {
+            remove_duplicates: true,
+            ..FormatOptions::default()
+        });
+        let result = formatter.format_document(input, None);
+        assert!(result.content.contains(r#"class="flex p-4""#));
+        assert_eq!(result.duplicates_removed, 1);
+
+        // Duplicates are kept side by side unless the option is enabled
+        let result = format_document(input);
+        assert!(result.contains(r#"class="flex p-4 p-4""#));
+    }
+
+    #[test]
+    fn test_lint_document_reports_conflicts() {
+        let input = "<div>\n  <p class=\"p-4 flex p-2\">content</p>\n</div>";
+        let formatter = TailwindFormatter::new(false);
+        let diagnostics = formatter.lint_document(input);
+        
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, LintKind::Conflict);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (2, 22));
+        assert!(diagnostics[0].message.contains("`p-2` conflicts with `p-4`"));
+    }
+
+    #[test]
+    fn test_fix_shorthands_option() {
+        let input = r#"<div class="mb-2 w-full flex mt-2 h-full">content</div>"#;
+        let formatter = TailwindFormatter::new(false).with_options(FormatOptions {
+            fix_shorthands: true,
+            ..FormatOptions::default()
+        });
+        let result = formatter.format_document(input, None);
+        assert!(result.content.contains(r#"class="flex my-2 size-full""#));
+        assert_eq!(result.shorthands_collapsed, 2);
+
+        let diagnostics = formatter.lint_document(input);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().all(|d| d.kind == LintKind::Shorthand));
+    }
+
+    #[test]
+    fn test_normalize_whitespace_option() {
+        let input = "<div class=\"  flex\tp-4   text-red-500 \">content</div>";
+        let result = format_document(input);
+        assert_eq!(result, input); // Already sorted, left alone by default
+
+        let formatter = TailwindFormatter::new(false).with_options(FormatOptions {
+            normalize_whitespace: true,
+            ..FormatOptions::default()
+        });
+        let result = formatter.format_document(input, None);
+        assert_eq!(result.content, r#"<div class="flex p-4 text-red-500">content</div>"#);
+        assert_eq!(result.changes.len(), 1);
+        assert_eq!(result.changes[0].kind, ChangeKind::Whitespace);
+    }
+
+    #[test]
+    fn test_multiple_attributes_changing_length() {
+        let input = r#"<a class="p-4 flex p-4">a</a><b class="text-sm  p-2">b</b>"#;
+        let formatter = TailwindFormatter::new(false).with_options(FormatOptions {
+            remove_duplicates: true,
+            ..FormatOptions::default()
+        });
+        let result = formatter.format_document(input, None);
+        assert_eq!(result.content, r#"<a class="flex p-4">a</a><b class="p-2 text-sm">b</b>"#);
+        assert_eq!(result.changes.len(), 2);
+        assert!(result.changes[0].start < result.changes[1].start);
+    }
+
+    #[test]
+    fn test_multiline_layout_option() {
+        let input = "<div\n  class=\"\n    text-red-500 p-4\n    flex\n  \"\n>";
+        let formatter = TailwindFormatter::new(false).with_options(FormatOptions {
+            layout: MultilineLayout::OnePerLine,
+            ..FormatOptions::default()
+        });
+        let result = formatter.format_document(input, None);
+        assert_eq!(
+            result.content,
+            "<div\n  class=\"\n    flex\n    p-4\n    text-red-500\n  \"\n>"
+        );
+    }
+
+    #[test]
+    fn test_custom_attributes_and_functions() {
+        let input = r#"<div classList="text-red-500 p-4 flex" className={cn("text-sm m-2", active && 'p-4 flex', `hover:p-2 block`)}>{tw`p-2 flex`}</div>"#;
+        let formatter = TailwindFormatter::new(false).with_matchers(
+            &["classList".to_string()],
+            &["cn".to_string(), "tw".to_string()],
+        );
+        let result = formatter.format_document(input, None);
+        assert_eq!(
+            result.content,
+            r#"<div classList="flex p-4 text-red-500" className={cn("m-2 text-sm", active && 'flex p-4', `block hover:p-2`)}>{tw`flex p-2`}</div>"#
+        );
+        
+        // Without configuration only class and className are sorted
+        assert_eq!(format_document(input), input);
+    }
+
+    #[test]
+    fn test_function_template_with_interpolation_skipped() {
+        let input = "clsx(`p-4 flex ${x}`, \"p-4 flex\")";
+        let formatter = TailwindFormatter::new(false).with_matchers(&[], &["clsx".to_string()]);
+        let result = formatter.format_document(input, None);
+        assert_eq!(result.content, "clsx(`p-4 flex ${x}`, \"flex p-4\")");
+    }
+
+    #[test]
+    fn test_format_range() {
+        let source = "<a class=\"p-4 flex\">\n<b class=\"p-2 block\">\n<i class=\"m-1 grid\">\n";
+        let formatter = TailwindFormatter::new(false);
+
+        let result = formatter.format_range(source, line_range(source, 2, 2), None);
+        assert_eq!(result.content, "<a class=\"p-4 flex\">\n<b class=\"block p-2\">\n<i class=\"m-1 grid\">\n");
+        assert_eq!(result.changes.len(), 1);
+
+        // Attributes partly inside the range are formatted whole
+        let edits = formatter.format_range_edits(source, 15..40);
+        assert_eq!(
+            edits,
+            vec![
+                TextEdit { start: 3, end: 19, text: "class=\"flex p-4\"".to_string() },
+                TextEdit { start: 24, end: 41, text: "class=\"block p-2\"".to_string() },
+            ]
+        );
+        assert_eq!(
+            TextEdit::apply(source, &edits),
+            "<a class=\"flex p-4\">\n<b class=\"block p-2\">\n<i class=\"m-1 grid\">\n"
+        );
+        assert!(formatter.format_range_edits(source, 19..24).is_empty());
+    }
+
+    #[test]
+    fn test_crlf_positions() {
+        let formatter = TailwindFormatter::new(true);
+        let source = "<main>\r\n<div class=\"p-4 flex\">\r\n  <p class=\"\r\n    m-2 block\r\n    p-4 grid\r\n  \">x</p>\r\n</main>\r\n";
+        let offset = source.find(">x<").unwrap() + 1;
+        let result = formatter.format_document(source, Some(CursorPosition { line: 0, column: 0, offset }));
+
+        assert_eq!(
+            result.content,
+            "<main>\r\n<div class=\"flex p-4\">\r\n  <p class=\"\r\n    block grid\r\n    m-2 p-4\r\n  \">x</p>\r\n</main>\r\n"
+        );
+        let positions: Vec<_> = result
+            .changes
+            .iter()
+            .map(|change| (change.line, change.column, change.end_line, change.end_column))
+            .collect();
+        assert_eq!(positions, vec![(1, 5, 1, 21), (2, 5, 5, 3)]);
+        let cursor = result.cursor_position.unwrap();
+        assert_eq!((cursor.line, cursor.column, cursor.offset), (5, 4, offset));
+    }
+
+    #[test]
+    fn test_cursor_inside_multibyte_character() {
+        let formatter = TailwindFormatter::new(true);
+        let source = "<p>é</p><div class=\"p-4 flex\">x</div>\n";
+        // Offset 4 is inside `é` (bytes 3..5)
+        let result = formatter.format_document(source, Some(CursorPosition { line: 0, column: 0, offset: 4 }));
+
+        assert_eq!(result.content, "<p>é</p><div class=\"flex p-4\">x</div>\n");
+        let cursor = result.cursor_position.unwrap();
+        assert_eq!((cursor.line, cursor.column, cursor.offset), (0, 3, 3));
+    }
+
+    #[test]
+    fn test_adjusted_cursor_stays_on_char_boundary() {
+        let formatter = TailwindFormatter::new(true);
+        let source = "<p class=\"before:content-['ééé'] p-4 flex\n after:content-['→→'] m-2\">";
+        for offset in 0..=source.len() {
+            let result = formatter.format_document(source, Some(CursorPosition { line: 0, column: 0, offset }));
+            assert!(result.changed);
+            let cursor = result.cursor_position.unwrap();
+            assert!(result.content.is_char_boundary(cursor.offset), "offset {offset} moved to {}", cursor.offset);
+        }
+    }
+
+    #[test]
+    fn test_line_range() {
+        let source = "one\ntwo\nthree";
+        assert_eq!(&source[line_range(source, 1, 1)], "one\n");
+        assert_eq!(&source[line_range(source, 2, 3)], "two\nthree");
+        assert_eq!(line_range(source, 5, 9), source.len()..source.len());
+    }
+
+}
@@ -0,0 +1,118 @@
+use crate::error::SorterError;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+/// Serializes index updates, which take git's index lock
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// A file as recorded in the git index
+#[derive(Debug, Clone)]
+pub struct StagedFile {
+    /// Path relative to the repository root
+    pub path: String,
+    pub mode: String,
+    pub content: Vec<u8>,
+}
+
+/// Run git in the current directory with `input` on stdin, returning stdout
+fn git(args: &[&str], input: Option<&[u8]>) -> Result<Vec<u8>, SorterError> {
+    let mut child = Command::new("git")
+        .args(args)
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| SorterError::Git(format!("Failed to run git: {err}")))?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin
+            .write_all(input)
+            .map_err(|err| SorterError::Git(format!("Failed to write to git: {err}")))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|err| SorterError::Git(format!("Failed to run git: {err}")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(SorterError::Git(format!("git {} failed: {}", args[0], stderr.trim())));
+    }
+    Ok(output.stdout)
+}
+
+/// Split NUL-terminated paths printed with `-z`
+fn split_paths(output: &[u8]) -> Vec<String> {
+    output
+        .split(|&byte| byte == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| String::from_utf8_lossy(path).to_string())
+        .collect()
+}
+
+/// Files added, copied, modified or renamed in the index, relative to the
+/// current directory and limited to the files below it and to `pathspecs`
+pub fn staged_files(pathspecs: &[&String]) -> Result<Vec<String>, SorterError> {
+    let mut args = vec!["diff", "--cached", "--name-only", "-z", "--relative", "--diff-filter=ACMR", "--"];
+    args.extend(pathspecs.iter().map(|pathspec| pathspec.as_str()));
+    Ok(split_paths(&git(&args, None)?))
+}
+
+/// Files changed in the working tree since the merge base of `since` and
+/// `HEAD`, including untracked files, relative to the current directory
+pub fn changed_files(since: &str, pathspecs: &[&String]) -> Result<Vec<String>, SorterError> {
+    let merge_base = git(&["merge-base", since, "HEAD"], None)?;
+    let merge_base = String::from_utf8_lossy(&merge_base).trim().to_string();
+
+    let mut args = vec!["diff", "--name-only", "-z", "--relative", "--diff-filter=ACMR", merge_base.as_str(), "--"];
+    args.extend(pathspecs.iter().map(|pathspec| pathspec.as_str()));
+    let mut files = split_paths(&git(&args, None)?);
+
+    let mut args = vec!["ls-files", "--others", "--exclude-standard", "-z", "--"];
+    args.extend(pathspecs.iter().map(|pathspec| pathspec.as_str()));
+    for file in split_paths(&git(&args, None)?) {
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Read the staged version of a file, given relative to the current directory
+pub fn read_staged(file_path: &str) -> Result<StagedFile, SorterError> {
+    // "<mode> <object> <stage>\t<path>"
+    let entry = git(&["ls-files", "--stage", "--full-name", "-z", "--", file_path], None)?;
+    let entry = String::from_utf8_lossy(&entry);
+    let entry = entry.split('\0').next().unwrap_or_default();
+    let (info, path) = entry
+        .split_once('\t')
+        .ok_or_else(|| SorterError::Git(format!("'{file_path}' is not staged")))?;
+    let mut info = info.split(' ');
+    let (Some(mode), Some(object), Some("0")) = (info.next(), info.next(), info.next()) else {
+        return Err(SorterError::Git(format!("'{file_path}' has unresolved merge conflicts")));
+    };
+
+    Ok(StagedFile {
+        path: path.to_string(),
+        mode: mode.to_string(),
+        content: git(&["cat-file", "blob", object], None)?,
+    })
+}
+
+/// Replace the staged content of a file
+pub fn stage(file: &StagedFile, content: &[u8]) -> Result<(), SorterError> {
+    let object = git(&["hash-object", "-w", "--no-filters", "--stdin"], Some(content))?;
+    let object = String::from_utf8_lossy(&object).trim().to_string();
+
+    let _lock = INDEX_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let entry = format!("{} {}\t{}\0", file.mode, object, file.path);
+    git(&["update-index", "-z", "--index-info"], Some(entry.as_bytes()))?;
+    Ok(())
+}
+
+/// Whether the working tree copy of a file differs from its staged content,
+/// i.e. only some of its changes are staged
+pub fn has_unstaged_changes(file_path: &str) -> Result<bool, SorterError> {
+    let output = git(&["diff", "--name-only", "-z", "--", file_path], None)?;
+    Ok(!output.is_empty())
+}
@@ -0,0 +1,231 @@
+use crate::config::{Config, CONFIG_FILES};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where editors find the JSON Schema when the package is installed with npm
+pub const SCHEMA_PATH: &str = "./node_modules/biome-tailwind-sorter/tailwindsorter.schema.json";
+
+/// Files visited at most when looking for the file types in use
+const MAX_SCANNED_FILES: usize = 10_000;
+
+/// Class name helpers from npm packages, and the functions they provide
+const CLASS_HELPERS: [(&str, &[&str]); 5] = [
+    ("clsx", &["clsx"]),
+    ("classnames", &["classNames"]),
+    ("tailwind-merge", &["twMerge", "cn"]),
+    ("class-variance-authority", &["cva"]),
+    ("tailwind-variants", &["tv"]),
+];
+
+/// What `init` found out about a project
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectInfo {
+    /// Version of the `tailwindcss` dependency in package.json
+    pub tailwind_version: Option<String>,
+    /// Supported file extensions present in the project, sorted
+    pub extensions: Vec<String>,
+    /// Frameworks implied by the file extensions
+    pub frameworks: Vec<String>,
+    /// Class helper functions from the package.json dependencies
+    pub functions: Vec<String>,
+    pub biome_config: Option<PathBuf>,
+    pub tailwind_config: Option<PathBuf>,
+    /// A sorter config that already exists in the directory
+    pub existing_config: Option<PathBuf>,
+}
+
+impl ProjectInfo {
+    /// Inspect the project in `dir`
+    pub fn detect(dir: &Path) -> Self {
+        let mut info = ProjectInfo {
+            existing_config: CONFIG_FILES
+                .iter()
+                .map(|name| dir.join(name))
+                .find(|path| path.is_file()),
+            biome_config: ["biome.json", "biome.jsonc"]
+                .iter()
+                .map(|name| dir.join(name))
+                .find(|path| path.is_file()),
+            tailwind_config: ["js", "cjs", "mjs", "ts"]
+                .iter()
+                .map(|ext| dir.join(format!("tailwind.config.{ext}")))
+                .find(|path| path.is_file()),
+            ..ProjectInfo::default()
+        };
+
+        if let Some(package) = fs::read_to_string(dir.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        {
+            let dependency = |name: &str| {
+                ["dependencies", "devDependencies", "peerDependencies"]
+                    .iter()
+                    .find_map(|section| package.get(section)?.get(name)?.as_str())
+                    .map(String::from)
+            };
+            info.tailwind_version = dependency("tailwindcss");
+            for (package_name, functions) in CLASS_HELPERS {
+                if dependency(package_name).is_some() {
+                    info.functions.extend(functions.iter().map(|name| name.to_string()));
+                }
+            }
+        }
+
+        let supported = Config::default().extensions;
+        let mut scanned = 0;
+        scan_extensions(dir, &supported, &mut info.extensions, &mut scanned);
+        info.extensions.sort();
+
+        for (extensions, framework) in [
+            (&["jsx", "tsx"][..], "React"),
+            (&["vue"][..], "Vue"),
+            (&["astro"][..], "Astro"),
+            (&["html"][..], "HTML"),
+        ] {
+            if extensions.iter().any(|ext| info.extensions.iter().any(|found| found == ext)) {
+                info.frameworks.push(framework.to_string());
+            }
+        }
+
+        info
+    }
+
+    /// A starter config for the project
+    pub fn starter_config(&self, with_schema: bool) -> Config {
+        let mut config = Config {
+            schema: with_schema.then(|| SCHEMA_PATH.to_string()),
+            ..Config::default()
+        };
+        if !self.extensions.is_empty() {
+            config.extensions = self.extensions.clone();
+        }
+        // Biome's useSortedClasses options are picked up on their own
+        if self.biome_config.is_none() {
+            config.functions = self.functions.clone();
+        }
+        config
+    }
+
+    /// Human readable lines describing what was detected
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        match &self.tailwind_version {
+            Some(version) => lines.push(format!("Tailwind CSS {version} (package.json)")),
+            None => lines.push("Tailwind CSS not found in package.json".to_string()),
+        }
+        if let Some(path) = &self.tailwind_config {
+            lines.push(format!("Tailwind config: {}", path.display()));
+        }
+        if self.extensions.is_empty() {
+            lines.push("No supported files found, keeping the default extensions".to_string());
+        } else {
+            lines.push(format!("File types: {}", self.extensions.join(", ")));
+        }
+        if !self.frameworks.is_empty() {
+            lines.push(format!("Frameworks: {}", self.frameworks.join(", ")));
+        }
+        if let Some(path) = &self.biome_config {
+            lines.push(format!(
+                "Biome config: {} (its useSortedClasses options are used automatically)",
+                path.display()
+            ));
+        } else if !self.functions.is_empty() {
+            lines.push(format!("Class helpers: {}", self.functions.join(", ")));
+        }
+        lines
+    }
+}
+
+/// Collect the supported extensions of files under `dir`, skipping hidden
+/// directories, the default ignores and tool configs like
+/// `tailwind.config.ts`, which hold no markup
+fn scan_extensions(dir: &Path, supported: &[String], found: &mut Vec<String>, scanned: &mut usize) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let ignored = Config::default().ignore;
+
+    for entry in entries.flatten() {
+        if *scanned >= MAX_SCANNED_FILES || found.len() == supported.len() {
+            return;
+        }
+        *scanned += 1;
+
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if !name.starts_with('.') && !ignored.contains(&name) {
+                scan_extensions(&path, supported, found, scanned);
+            }
+        } else if name.contains(".config.") {
+            continue;
+        } else if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+            if supported.iter().any(|supported| supported == ext) && !found.iter().any(|f| f == ext) {
+                found.push(ext.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("package.json"),
+            r#"{ "dependencies": { "clsx": "^2.0.0" }, "devDependencies": { "tailwindcss": "^4.1.0" } }"#,
+        ).unwrap();
+        fs::write(root.join("tailwind.config.ts"), "export default {}").unwrap();
+        fs::create_dir_all(root.join("src/components")).unwrap();
+        fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        fs::write(root.join("src/components/Button.tsx"), "").unwrap();
+        fs::write(root.join("src/index.html"), "").unwrap();
+        fs::write(root.join("src/styles.css"), "").unwrap();
+        fs::write(root.join("vite.config.js"), "").unwrap();
+        fs::write(root.join("node_modules/pkg/index.vue"), "").unwrap();
+
+        let info = ProjectInfo::detect(root);
+        assert_eq!(info.tailwind_version.as_deref(), Some("^4.1.0"));
+        assert_eq!(info.extensions, vec!["html", "tsx"]);
+        assert_eq!(info.frameworks, vec!["React", "HTML"]);
+        assert_eq!(info.functions, vec!["clsx"]);
+        assert!(info.tailwind_config.unwrap().ends_with("tailwind.config.ts"));
+        assert!(info.biome_config.is_none());
+        assert!(info.existing_config.is_none());
+    }
+
+    #[test]
+    fn test_starter_config() {
+        let info = ProjectInfo {
+            tailwind_version: Some("~3.3.0".to_string()),
+            extensions: vec!["vue".to_string()],
+            functions: vec!["clsx".to_string()],
+            ..ProjectInfo::default()
+        };
+        let config = info.starter_config(true);
+        assert_eq!(config.extensions, vec!["vue"]);
+        assert_eq!(config.functions, vec!["clsx"]);
+        assert!(!config.fix_shorthands);
+        assert_eq!(config.schema.as_deref(), Some(SCHEMA_PATH));
+
+        // With a biome.json the class helpers come from Biome's options
+        let info = ProjectInfo {
+            biome_config: Some(PathBuf::from("biome.json")),
+            ..info
+        };
+        assert!(info.starter_config(false).functions.is_empty());
+
+        // Shorthand fixes stay opt-in, whatever the Tailwind CSS version
+        let info = ProjectInfo { tailwind_version: Some("^4.1.0".to_string()), ..info };
+        assert!(!info.starter_config(false).fix_shorthands);
+        assert_eq!(ProjectInfo::default().starter_config(false).extensions, Config::default().extensions);
+    }
+}
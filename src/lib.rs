@@ -2,18 +2,44 @@ pub mod tailwind_order;
 pub mod class_extractor;
 pub mod formatter;
 pub mod config;
+pub mod lint;
+pub mod init;
+pub mod diff;
+pub mod report;
+pub mod cache;
+pub mod git;
+pub mod watch;
+pub mod error;
+pub mod encoding;
 
-pub use formatter::{TailwindFormatter, CursorPosition, FormatResult};
-pub use tailwind_order::{sort_tailwind_classes, parse_tailwind_class, TailwindClass};
+pub use formatter::{
+    line_range, AttributeChange, ChangeKind, CursorPosition, FormatOptions, FormatResult,
+    TailwindFormatter, TextEdit,
+};
+pub use tailwind_order::{sort_tailwind_classes, parse_tailwind_class, Sorter, TailwindClass};
 pub use class_extractor::{
     extract_class_names, 
     reconstruct_class_string, 
+    layout_class_string,
+    normalize_class_whitespace,
+    remove_duplicate_classes,
     contains_tailwind_classes, 
     is_tailwind_class,
     ClassAttribute,
+    MultilineLayout,
     QuoteType
 };
-pub use config::Config;
+pub use cache::Cache;
+pub use config::{Config, ConfigOverride, ConfigResolver, PathPatterns};
+pub use diff::unified_diff;
+pub use encoding::SourceText;
+pub use error::SorterError;
+pub use init::ProjectInfo;
+pub use report::{ChangeRecord, FileRecord, Reporter, Summary};
+pub use lint::{
+    collapse_shorthands, css_property, find_conflicts, find_shorthands, Conflict, LintDiagnostic,
+    LintKind, Shorthand,
+};
 
 // Re-export the main formatting function for convenience
 pub fn format_document(source: &str) -> String {
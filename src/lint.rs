@@ -0,0 +1,718 @@
+use crate::tailwind_order::{parse_tailwind_class, TailwindClass};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LintKind {
+    /// A later class overrides an earlier one setting the same CSS property
+    Conflict,
+    /// Two classes can be collapsed into a single shorthand
+    Shorthand,
+}
+
+/// A lint finding for a single class inside a class attribute
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct LintDiagnostic {
+    pub kind: LintKind,
+    /// 1-based line of the offending class
+    pub line: usize,
+    /// 1-based column of the offending class
+    pub column: usize,
+    /// Byte offset of the offending class in the document
+    pub offset: usize,
+    pub message: String,
+}
+
+/// Two classes with the same variants that set the same CSS property
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// Index of the overridden class
+    pub first: usize,
+    /// Index of the overriding class
+    pub second: usize,
+    pub property: &'static str,
+}
+
+/// Two classes that can be replaced by a single shorthand class
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shorthand {
+    pub first: usize,
+    pub second: usize,
+    pub replacement: String,
+}
+
+// Pairs of utility prefixes and the shorthand setting both
+static SHORTHAND_PAIRS: &[(&str, &str, &str)] = &[
+    ("px", "py", "p"),
+    ("pt", "pb", "py"),
+    ("pl", "pr", "px"),
+    ("mx", "my", "m"),
+    ("mt", "mb", "my"),
+    ("ml", "mr", "mx"),
+    ("w", "h", "size"),
+    ("inset-x", "inset-y", "inset"),
+    ("top", "bottom", "inset-y"),
+    ("left", "right", "inset-x"),
+    ("gap-x", "gap-y", "gap"),
+    ("scale-x", "scale-y", "scale"),
+    ("overflow-x", "overflow-y", "overflow"),
+    ("overscroll-x", "overscroll-y", "overscroll"),
+];
+
+// Values of `w-*`/`h-*` that have no `size-*` equivalent
+static NON_SIZE_VALUES: &[&str] = &["screen", "svw", "lvw", "dvw", "svh", "lvh", "dvh"];
+
+// Standalone utilities, keyed by their full name
+static KEYWORD_PROPERTIES: &[(&str, &str)] = &[
+    ("block", "display"),
+    ("inline-block", "display"),
+    ("inline", "display"),
+    ("flex", "display"),
+    ("inline-flex", "display"),
+    ("table", "display"),
+    ("inline-table", "display"),
+    ("table-caption", "display"),
+    ("table-cell", "display"),
+    ("table-column", "display"),
+    ("table-column-group", "display"),
+    ("table-footer-group", "display"),
+    ("table-header-group", "display"),
+    ("table-row-group", "display"),
+    ("table-row", "display"),
+    ("flow-root", "display"),
+    ("grid", "display"),
+    ("inline-grid", "display"),
+    ("contents", "display"),
+    ("list-item", "display"),
+    ("hidden", "display"),
+    ("static", "position"),
+    ("fixed", "position"),
+    ("absolute", "position"),
+    ("relative", "position"),
+    ("sticky", "position"),
+    ("visible", "visibility"),
+    ("invisible", "visibility"),
+    ("collapse", "visibility"),
+    ("box-border", "box-sizing"),
+    ("box-content", "box-sizing"),
+    ("uppercase", "text-transform"),
+    ("lowercase", "text-transform"),
+    ("capitalize", "text-transform"),
+    ("normal-case", "text-transform"),
+    ("italic", "font-style"),
+    ("not-italic", "font-style"),
+    ("underline", "text-decoration-line"),
+    ("overline", "text-decoration-line"),
+    ("line-through", "text-decoration-line"),
+    ("no-underline", "text-decoration-line"),
+    ("flex-row", "flex-direction"),
+    ("flex-row-reverse", "flex-direction"),
+    ("flex-col", "flex-direction"),
+    ("flex-col-reverse", "flex-direction"),
+    ("flex-wrap", "flex-wrap"),
+    ("flex-wrap-reverse", "flex-wrap"),
+    ("flex-nowrap", "flex-wrap"),
+    ("border-collapse", "border-collapse"),
+    ("border-separate", "border-collapse"),
+    ("isolate", "isolation"),
+    ("isolation-auto", "isolation"),
+    ("truncate", "text-overflow"),
+    ("sr-only", "sr-only"),
+    ("not-sr-only", "sr-only"),
+];
+
+// Utilities taking a value, keyed by their prefix (the longest prefix wins)
+static PREFIX_PROPERTIES: &[(&str, &str)] = &[
+    ("p", "padding"),
+    ("px", "padding-inline"),
+    ("py", "padding-block"),
+    ("pt", "padding-top"),
+    ("pr", "padding-right"),
+    ("pb", "padding-bottom"),
+    ("pl", "padding-left"),
+    ("ps", "padding-inline-start"),
+    ("pe", "padding-inline-end"),
+    ("m", "margin"),
+    ("mx", "margin-inline"),
+    ("my", "margin-block"),
+    ("mt", "margin-top"),
+    ("mr", "margin-right"),
+    ("mb", "margin-bottom"),
+    ("ml", "margin-left"),
+    ("ms", "margin-inline-start"),
+    ("me", "margin-inline-end"),
+    ("space-x", "space-x"),
+    ("space-y", "space-y"),
+    ("gap", "gap"),
+    ("gap-x", "column-gap"),
+    ("gap-y", "row-gap"),
+    ("w", "width"),
+    ("min-w", "min-width"),
+    ("max-w", "max-width"),
+    ("h", "height"),
+    ("min-h", "min-height"),
+    ("max-h", "max-height"),
+    ("size", "size"),
+    ("inset", "inset"),
+    ("inset-x", "inset-inline"),
+    ("inset-y", "inset-block"),
+    ("top", "top"),
+    ("right", "right"),
+    ("bottom", "bottom"),
+    ("left", "left"),
+    ("start", "inset-inline-start"),
+    ("end", "inset-inline-end"),
+    ("z", "z-index"),
+    ("flex", "flex"),
+    ("flex-grow", "flex-grow"),
+    ("flex-shrink", "flex-shrink"),
+    ("basis", "flex-basis"),
+    ("grow", "flex-grow"),
+    ("shrink", "flex-shrink"),
+    ("order", "order"),
+    ("grid-cols", "grid-template-columns"),
+    ("grid-rows", "grid-template-rows"),
+    ("grid-flow", "grid-auto-flow"),
+    ("col", "grid-column"),
+    ("col-span", "grid-column"),
+    ("col-start", "grid-column-start"),
+    ("col-end", "grid-column-end"),
+    ("row", "grid-row"),
+    ("row-span", "grid-row"),
+    ("row-start", "grid-row-start"),
+    ("row-end", "grid-row-end"),
+    ("auto-cols", "grid-auto-columns"),
+    ("auto-rows", "grid-auto-rows"),
+    ("justify", "justify-content"),
+    ("justify-items", "justify-items"),
+    ("justify-self", "justify-self"),
+    ("content", "align-content"),
+    ("items", "align-items"),
+    ("self", "align-self"),
+    ("place-content", "place-content"),
+    ("place-items", "place-items"),
+    ("place-self", "place-self"),
+    ("leading", "line-height"),
+    ("tracking", "letter-spacing"),
+    ("whitespace", "white-space"),
+    ("align", "vertical-align"),
+    ("overflow", "overflow"),
+    ("overflow-x", "overflow-x"),
+    ("overflow-y", "overflow-y"),
+    ("overscroll", "overscroll-behavior"),
+    ("aspect", "aspect-ratio"),
+    ("columns", "columns"),
+    ("float", "float"),
+    ("clear", "clear"),
+    ("opacity", "opacity"),
+    ("mix-blend", "mix-blend-mode"),
+    ("bg-blend", "background-blend-mode"),
+    ("bg-clip", "background-clip"),
+    ("bg-origin", "background-origin"),
+    ("rounded", "border-radius"),
+    ("rounded-t", "border-top-radius"),
+    ("rounded-r", "border-right-radius"),
+    ("rounded-b", "border-bottom-radius"),
+    ("rounded-l", "border-left-radius"),
+    ("rounded-tl", "border-top-left-radius"),
+    ("rounded-tr", "border-top-right-radius"),
+    ("rounded-br", "border-bottom-right-radius"),
+    ("rounded-bl", "border-bottom-left-radius"),
+    ("border-spacing", "border-spacing"),
+    ("blur", "blur"),
+    ("brightness", "brightness"),
+    ("contrast", "contrast"),
+    ("drop-shadow", "drop-shadow"),
+    ("grayscale", "grayscale"),
+    ("hue-rotate", "hue-rotate"),
+    ("invert", "invert"),
+    ("saturate", "saturate"),
+    ("sepia", "sepia"),
+    ("backdrop-blur", "backdrop-blur"),
+    ("backdrop-brightness", "backdrop-brightness"),
+    ("backdrop-contrast", "backdrop-contrast"),
+    ("backdrop-grayscale", "backdrop-grayscale"),
+    ("backdrop-opacity", "backdrop-opacity"),
+    ("backdrop-saturate", "backdrop-saturate"),
+    ("transition", "transition-property"),
+    ("duration", "transition-duration"),
+    ("ease", "transition-timing-function"),
+    ("delay", "transition-delay"),
+    ("animate", "animation"),
+    ("scale", "scale"),
+    ("scale-x", "scale-x"),
+    ("scale-y", "scale-y"),
+    ("rotate", "rotate"),
+    ("translate-x", "translate-x"),
+    ("translate-y", "translate-y"),
+    ("skew-x", "skew-x"),
+    ("skew-y", "skew-y"),
+    ("origin", "transform-origin"),
+    ("cursor", "cursor"),
+    ("select", "user-select"),
+    ("pointer-events", "pointer-events"),
+    ("resize", "resize"),
+    ("touch", "touch-action"),
+    ("will-change", "will-change"),
+    ("accent", "accent-color"),
+    ("caret", "caret-color"),
+    ("fill", "fill"),
+    ("line-clamp", "line-clamp"),
+    ("indent", "text-indent"),
+    ("underline-offset", "text-underline-offset"),
+];
+
+/// Strip a `family-` prefix, returning the rest of the utility
+fn family_value<'a>(utility: &'a str, family: &str) -> Option<&'a str> {
+    utility.strip_prefix(family)?.strip_prefix('-')
+}
+
+fn is_length_value(value: &str) -> bool {
+    value.chars().all(|c| c.is_ascii_digit() || c == '.')
+        || value == "px"
+        || (value.starts_with('[') && value[1..].starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Whether a value is a gradient color stop position like `10%` or `[12.5%]`
+fn is_position_value(value: &str) -> bool {
+    value.strip_prefix('[').and_then(|v| v.strip_suffix(']')).unwrap_or(value).ends_with('%')
+}
+
+// Border sides with the properties of their width and color
+static BORDER_SIDES: &[(&str, &str, &str)] = &[
+    ("x", "border-inline-width", "border-inline-color"),
+    ("y", "border-block-width", "border-block-color"),
+    ("t", "border-top-width", "border-top-color"),
+    ("r", "border-right-width", "border-right-color"),
+    ("b", "border-bottom-width", "border-bottom-color"),
+    ("l", "border-left-width", "border-left-color"),
+    ("s", "border-inline-start-width", "border-inline-start-color"),
+    ("e", "border-inline-end-width", "border-inline-end-color"),
+];
+
+// Tailwind 3 color opacity utilities, which set their own custom property
+static OPACITY_PROPERTIES: &[(&str, &str)] = &[
+    ("bg-opacity", "--tw-bg-opacity"),
+    ("text-opacity", "--tw-text-opacity"),
+    ("border-opacity", "--tw-border-opacity"),
+    ("divide-opacity", "--tw-divide-opacity"),
+    ("placeholder-opacity", "--tw-placeholder-opacity"),
+    ("ring-opacity", "--tw-ring-opacity"),
+];
+
+// Gradient color stops with the properties of their color and position
+static GRADIENT_STOPS: &[(&str, &str, &str)] = &[
+    ("from", "gradient-from", "gradient-from-position"),
+    ("via", "gradient-via", "gradient-via-position"),
+    ("to", "gradient-to", "gradient-to-position"),
+];
+
+/// The CSS property a utility sets, if it is known
+///
+/// Utilities whose prefix is shared by several properties (`text-`,
+/// `font-`, `bg-`, `border-`, ...) are disambiguated by their value.
+pub fn css_property(utility: &str) -> Option<&'static str> {
+    let utility = utility.trim_start_matches('!').trim_start_matches('-');
+
+    // Arbitrary properties like [mask-type:luminance]
+    if utility.starts_with('[') && utility.ends_with(']') {
+        let property = utility[1..].split(':').next()?;
+        return KEYWORD_PROPERTIES
+            .iter()
+            .chain(PREFIX_PROPERTIES)
+            .map(|(_, p)| *p)
+            .find(|p| *p == property)
+            .or(Some("arbitrary-property"));
+    }
+
+    if let Some((_, property)) = KEYWORD_PROPERTIES.iter().find(|(k, _)| *k == utility) {
+        return Some(property);
+    }
+
+    let opacity = OPACITY_PROPERTIES.iter().find(|(p, _)| family_value(utility, p).is_some());
+    if let Some((_, property)) = opacity {
+        return Some(property);
+    }
+
+    if let Some(value) = family_value(utility, "text") {
+        // `text-sm/6` also sets the line height
+        let value = match value.split_once('/') {
+            Some((size, _)) if !value.starts_with('[') => size,
+            _ => value,
+        };
+        return Some(match value {
+            "xs" | "sm" | "base" | "lg" | "xl" => "font-size",
+            v if v.ends_with("xl") && v[..v.len() - 2].chars().all(|c| c.is_ascii_digit()) => {
+                "font-size"
+            }
+            "left" | "center" | "right" | "justify" | "start" | "end" => "text-align",
+            "ellipsis" | "clip" => "text-overflow",
+            "wrap" | "nowrap" | "balance" | "pretty" => "text-wrap",
+            v if is_length_value(v) || v.starts_with("[length:") => "font-size",
+            _ => "color",
+        });
+    }
+
+    if let Some(value) = family_value(utility, "font") {
+        return Some(match value {
+            "thin" | "extralight" | "light" | "normal" | "medium" | "semibold" | "bold"
+            | "extrabold" | "black" => "font-weight",
+            _ => "font-family",
+        });
+    }
+
+    if let Some(value) = family_value(utility, "bg") {
+        if value.starts_with("clip-") || value.starts_with("origin-") || value.starts_with("blend-") {
+            // Handled by the prefix table below
+        } else {
+            return Some(match value {
+                "fixed" | "local" | "scroll" => "background-attachment",
+                "bottom" | "center" | "left" | "left-bottom" | "left-top" | "right"
+                | "right-bottom" | "right-top" | "top" => "background-position",
+                "repeat" | "no-repeat" | "repeat-x" | "repeat-y" | "repeat-round"
+                | "repeat-space" => "background-repeat",
+                "auto" | "cover" | "contain" => "background-size",
+                v if v == "none" || v.starts_with("gradient-") => "background-image",
+                _ => "background-color",
+            });
+        }
+    }
+
+    if utility == "border" {
+        return Some("border-width");
+    }
+    if let Some(value) = family_value(utility, "border") {
+        match value {
+            "solid" | "dashed" | "dotted" | "double" | "hidden" | "none" => {
+                return Some("border-style")
+            }
+            v if is_length_value(v) => return Some("border-width"),
+            _ => {}
+        }
+
+        let side = value.split('-').next().unwrap_or("");
+        if let Some((_, width, color)) = BORDER_SIDES.iter().find(|(s, _, _)| *s == side) {
+            return match value[side.len()..].strip_prefix('-') {
+                None => Some(width),
+                Some(v) if is_length_value(v) => Some(width),
+                Some(_) => Some(color),
+            };
+        }
+        if !value.starts_with("spacing") {
+            return Some("border-color");
+        }
+    }
+
+    if utility == "shadow" {
+        return Some("box-shadow");
+    }
+    if let Some(value) = family_value(utility, "shadow") {
+        return Some(match value {
+            "sm" | "md" | "lg" | "xl" | "2xl" | "inner" | "none" => "box-shadow",
+            _ => "box-shadow-color",
+        });
+    }
+
+    if utility == "ring" {
+        return Some("ring-width");
+    }
+    if let Some(value) = family_value(utility, "ring") {
+        if let Some(offset) = family_value(value, "offset") {
+            return Some(if is_length_value(offset) { "ring-offset-width" } else { "ring-offset-color" });
+        }
+        return Some(match value {
+            "inset" => "ring-inset",
+            v if is_length_value(v) => "ring-width",
+            _ => "ring-color",
+        });
+    }
+
+    for (family, color, position) in GRADIENT_STOPS {
+        if let Some(value) = family_value(utility, family) {
+            return Some(if is_position_value(value) { position } else { color });
+        }
+    }
+
+    if let Some(value) = family_value(utility, "stroke") {
+        return Some(if is_length_value(value) { "stroke-width" } else { "stroke" });
+    }
+
+    if let Some(value) = family_value(utility, "decoration") {
+        return Some(match value {
+            "solid" | "double" | "dotted" | "dashed" | "wavy" => "text-decoration-style",
+            "auto" | "from-font" => "text-decoration-thickness",
+            v if is_length_value(v) => "text-decoration-thickness",
+            _ => "text-decoration-color",
+        });
+    }
+
+    if let Some(value) = family_value(utility, "object") {
+        return Some(match value {
+            "contain" | "cover" | "fill" | "none" | "scale-down" => "object-fit",
+            _ => "object-position",
+        });
+    }
+
+    if let Some(value) = family_value(utility, "list") {
+        return Some(match value {
+            "inside" | "outside" => "list-style-position",
+            _ => "list-style-type",
+        });
+    }
+
+    PREFIX_PROPERTIES
+        .iter()
+        .filter(|(prefix, _)| {
+            utility == *prefix
+                || utility
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('-'))
+        })
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, property)| *property)
+}
+
+fn conflict_key(class: &TailwindClass) -> Option<(&'static str, Vec<&str>, bool)> {
+    // Template interpolations are not classes
+    if class.name.contains(['{', '}', '$', '?', '\'', '"', '`']) {
+        return None;
+    }
+    let property = css_property(class.utility())?;
+    Some((property, class.variant_set(), class.is_important()))
+}
+
+/// Find classes that override an earlier class with the same variants
+///
+/// Exact duplicates are not reported; see `remove_duplicate_classes`.
+pub fn find_conflicts(class_names: &[String]) -> Vec<Conflict> {
+    let parsed: Vec<TailwindClass> = class_names
+        .iter()
+        .map(|c| parse_tailwind_class(c))
+        .collect();
+    let keys: Vec<_> = parsed.iter().map(conflict_key).collect();
+    let mut conflicts = Vec::new();
+
+    for (second, second_key) in keys.iter().enumerate() {
+        let Some(second_key) = second_key else {
+            continue;
+        };
+        let first = (0..second).rev().find(|&first| {
+            keys[first].as_ref() == Some(second_key) && parsed[first].name != parsed[second].name
+        });
+        if let Some(first) = first {
+            conflicts.push(Conflict {
+                first,
+                second,
+                property: second_key.0,
+            });
+        }
+    }
+
+    conflicts
+}
+
+/// Split a class into its variant/modifier prefix (e.g. `md:!-`), the
+/// shorthand-pair prefix it uses and its value
+fn split_pair_class(class_name: &str) -> Option<(&str, &'static str, &str)> {
+    let utility_start = class_name.rfind(':').map_or(0, |i| i + 1);
+    let utility = &class_name[utility_start..];
+    let bare = utility.trim_start_matches('!').trim_start_matches('-');
+    let lead = &class_name[..class_name.len() - bare.len()];
+
+    SHORTHAND_PAIRS
+        .iter()
+        .flat_map(|(a, b, _)| [*a, *b])
+        .filter_map(|prefix| {
+            let value = bare.strip_prefix(prefix)?.strip_prefix('-')?;
+            Some((prefix, value))
+        })
+        .max_by_key(|(prefix, _)| prefix.len())
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(prefix, value)| (lead, prefix, value))
+}
+
+/// Find pairs of classes with identical variants and values that can be
+/// collapsed into a shorthand, e.g. `mt-2 mb-2` into `my-2`
+///
+/// Each class takes part in at most one pair.
+pub fn find_shorthands(class_names: &[String]) -> Vec<Shorthand> {
+    let parts: Vec<_> = class_names
+        .iter()
+        .map(|c| {
+            if c.contains(['{', '}', '$', '?', '\'', '"', '`']) {
+                None
+            } else {
+                split_pair_class(c)
+            }
+        })
+        .collect();
+    let mut used = vec![false; class_names.len()];
+    let mut shorthands = Vec::new();
+
+    for first in 0..class_names.len() {
+        let Some((lead, prefix, value)) = parts[first] else {
+            continue;
+        };
+        if used[first] {
+            continue;
+        }
+
+        for &(a, b, shorthand) in SHORTHAND_PAIRS {
+            let partner = if prefix == a {
+                b
+            } else if prefix == b {
+                a
+            } else {
+                continue;
+            };
+            if shorthand == "size" && NON_SIZE_VALUES.contains(&value) {
+                continue;
+            }
+
+            let second = (0..class_names.len()).find(|&i| {
+                !used[i] && i != first && parts[i] == Some((lead, partner, value))
+            });
+            if let Some(second) = second {
+                used[first] = true;
+                used[second] = true;
+                shorthands.push(Shorthand {
+                    first: first.min(second),
+                    second: first.max(second),
+                    replacement: format!("{lead}{shorthand}-{value}"),
+                });
+                break;
+            }
+        }
+    }
+
+    shorthands.sort_by_key(|s| s.first);
+    shorthands
+}
+
+/// Collapse shorthand pairs until none remain, so `pt-4 pb-4 pl-4 pr-4`
+/// becomes `p-4`. Returns the new classes and the number of pairs collapsed.
+pub fn collapse_shorthands(class_names: &[String]) -> (Vec<String>, usize) {
+    let mut classes = class_names.to_vec();
+    let mut collapsed = 0;
+
+    loop {
+        let shorthands = find_shorthands(&classes);
+        if shorthands.is_empty() {
+            return (classes, collapsed);
+        }
+        collapsed += shorthands.len();
+
+        let mut removed = vec![false; classes.len()];
+        for shorthand in shorthands {
+            classes[shorthand.first] = shorthand.replacement;
+            removed[shorthand.second] = true;
+        }
+        classes = classes
+            .into_iter()
+            .zip(removed)
+            .filter(|(_, removed)| !removed)
+            .map(|(class, _)| class)
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classes(input: &str) -> Vec<String> {
+        input.split_whitespace().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_css_property() {
+        assert_eq!(css_property("p-4"), Some("padding"));
+        assert_eq!(css_property("-mt-2"), Some("margin-top"));
+        assert_eq!(css_property("inset-x-0"), Some("inset-inline"));
+        assert_eq!(css_property("text-lg"), Some("font-size"));
+        assert_eq!(css_property("text-center"), Some("text-align"));
+        assert_eq!(css_property("text-red-500"), Some("color"));
+        assert_eq!(css_property("border-2"), Some("border-width"));
+        assert_eq!(css_property("border-red-500"), Some("border-color"));
+        assert_eq!(css_property("hidden"), Some("display"));
+        assert_eq!(css_property("w-[100px]"), Some("width"));
+        assert_eq!(css_property("card"), None);
+    }
+
+    #[test]
+    fn test_unrelated_utilities_do_not_conflict() {
+        for pair in [
+            "border-t-2 border-b-4",
+            "border-t-red-500 border-b-blue-500",
+            "ring-offset-2 ring-offset-white",
+            "from-10% from-red-500",
+            "via-[30%] via-blue-500",
+            "text-[14px] text-red-500",
+            "text-sm/6 text-gray-900",
+            "flex-1 flex-shrink-0",
+            "flex-1 flex-grow",
+            "bg-red-500 bg-opacity-50",
+            "text-white text-opacity-75",
+            "border-opacity-50 border-red-500",
+        ] {
+            assert!(find_conflicts(&classes(pair)).is_empty(), "`{pair}` should not conflict");
+        }
+        assert_eq!(css_property("text-[14px]"), Some("font-size"));
+        assert_eq!(css_property("text-sm/6"), Some("font-size"));
+        assert_eq!(css_property("text-red-500/50"), Some("color"));
+        assert_eq!(css_property("flex-shrink-0"), Some("flex-shrink"));
+        assert_eq!(css_property("bg-opacity-50"), Some("--tw-bg-opacity"));
+        assert_eq!(find_conflicts(&classes("flex-grow flex-grow-0"))[0].property, "flex-grow");
+        assert_eq!(find_conflicts(&classes("border-t-2 border-t-4"))[0].property, "border-top-width");
+        assert_eq!(find_conflicts(&classes("ring-offset-2 ring-offset-4"))[0].property, "ring-offset-width");
+        assert_eq!(find_conflicts(&classes("from-10% from-20%"))[0].property, "gradient-from-position");
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let conflicts = find_conflicts(&classes("p-4 flex p-2 hidden text-left text-center"));
+        assert_eq!(
+            conflicts,
+            vec![
+                Conflict { first: 0, second: 2, property: "padding" },
+                Conflict { first: 1, second: 3, property: "display" },
+                Conflict { first: 4, second: 5, property: "text-align" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_conflicts_respects_variants() {
+        assert!(find_conflicts(&classes("p-4 md:p-2 hover:p-2 px-4 text-lg text-red-500")).is_empty());
+        assert_eq!(find_conflicts(&classes("md:hover:p-4 hover:md:p-2")).len(), 1);
+        assert!(find_conflicts(&classes("p-4 p-4")).is_empty());
+    }
+
+    #[test]
+    fn test_find_shorthands() {
+        let shorthands = find_shorthands(&classes("mt-2 flex w-full mb-2 h-full px-4 py-2"));
+        assert_eq!(
+            shorthands,
+            vec![
+                Shorthand { first: 0, second: 3, replacement: "my-2".to_string() },
+                Shorthand { first: 2, second: 4, replacement: "size-full".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_shorthands_requires_same_variants() {
+        assert!(find_shorthands(&classes("md:px-4 py-4 hover:mt-2 focus:mb-2")).is_empty());
+        assert!(find_shorthands(&classes("w-screen h-screen -mt-2 mb-2")).is_empty());
+        assert_eq!(
+            find_shorthands(&classes("md:-mx-2 md:-my-2"))[0].replacement,
+            "md:-m-2"
+        );
+    }
+
+    #[test]
+    fn test_collapse_shorthands() {
+        let (result, collapsed) = collapse_shorthands(&classes("pt-4 flex pb-4 pl-4 pr-4"));
+        assert_eq!(result, vec!["p-4", "flex"]);
+        assert_eq!(collapsed, 3);
+    }
+}
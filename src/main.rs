@@ -2,12 +2,41 @@ mod tailwind_order;
 mod class_extractor;
 mod formatter;
 mod config;
+mod lint;
+mod init;
+mod diff;
+mod report;
+mod cache;
+mod git;
+mod watch;
+mod error;
+mod encoding;
 
-use clap::{Arg, Command};
-use formatter::{TailwindFormatter, CursorPosition};
+use cache::{Cache, DEFAULT_CACHE_DIR};
+use class_extractor::MultilineLayout;
+use clap::parser::ValueSource;
+use clap::{Arg, ArgMatches, Command};
+use config::{Config, ConfigResolver, PathPatterns};
+use encoding::SourceText;
+use encoding_rs::Encoding;
+use error::SorterError;
+use formatter::{TailwindFormatter, CursorPosition, FormatResult};
+use lint::LintDiagnostic;
+use globset::{GlobBuilder, GlobMatcher};
+use ignore::WalkBuilder;
+use init::ProjectInfo;
+use report::{FileRecord, Summary, REPORTERS};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::io::{self, Read, Write};
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
 
 fn main() {
     let matches = Command::new("biome-tailwind-sorter")
@@ -18,7 +47,48 @@ fn main() {
                 .help("Files to process")
                 .value_name("FILES")
                 .num_args(1..)
-                .required(true)
+                .required_unless_present_any(["stdin", "staged", "since"])
+        )
+        .arg(
+            Arg::new("stdin")
+                .long("stdin")
+                .help("Read source from stdin and write the formatted result to stdout")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["files", "write"])
+        )
+        .arg(
+            Arg::new("stdin-filepath")
+                .long("stdin-filepath")
+                .help("Path of the source read from stdin, used to pick the config and file type")
+                .value_name("PATH")
+                .requires("stdin")
+        )
+        .arg(
+            Arg::new("staged")
+                .long("staged")
+                .help("Process the staged content of staged files (restaging it with --write); FILES limit which")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["stdin", "since"])
+        )
+        .arg(
+            Arg::new("since")
+                .long("since")
+                .help("Process files changed since the merge base with a git ref, and untracked files; FILES limit which")
+                .value_name("REF")
+                .conflicts_with("stdin")
+        )
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .help("Keep running and process files again when they change")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["stdin", "staged", "since", "lint", "range", "lines", "reporter"])
+        )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .help("Path to a configuration file (skips config discovery)")
+                .value_name("PATH")
         )
         .arg(
             Arg::new("write")
@@ -34,6 +104,28 @@ fn main() {
                 .help("Check if files need sorting (exit code 1 if changes needed)")
                 .action(clap::ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("diff")
+                .long("diff")
+                .help("Print a unified diff of the changes each file needs (colored on a terminal)")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("reporter")
+                .long("reporter")
+                .help("Output format of the results")
+                .value_name("FORMAT")
+                .value_parser(REPORTERS)
+                .default_value("text")
+                .conflicts_with_all(["lint", "diff", "stdin"])
+        )
+        .arg(
+            Arg::new("lint")
+                .long("lint")
+                .help("Report conflicting classes and shorthand suggestions without rewriting files (exit code 1 if any found)")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("write")
+        )
         .arg(
             Arg::new("verbose")
                 .long("verbose")
@@ -41,6 +133,98 @@ fn main() {
                 .help("Verbose output")
                 .action(clap::ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("include")
+                .long("include")
+                .help("Only process files matching this glob (can be repeated)")
+                .value_name("GLOB")
+                .value_parser(parse_glob)
+                .action(clap::ArgAction::Append)
+        )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .help("Skip files matching this glob (can be repeated)")
+                .value_name("GLOB")
+                .value_parser(parse_glob)
+                .action(clap::ArgAction::Append)
+        )
+        .arg(
+            Arg::new("encoding")
+                .long("encoding")
+                .help("Encoding of files without a byte order mark, e.g. latin1 or shift_jis [default: utf-8]")
+                .value_name("ENCODING")
+                .value_parser(encoding::parse_encoding)
+        )
+        .arg(
+            Arg::new("threads")
+                .long("threads")
+                .short('j')
+                .help("Number of files processed in parallel [default: number of CPUs]")
+                .value_name("N")
+                .value_parser(clap::value_parser!(NonZeroUsize))
+        )
+        .arg(
+            Arg::new("no-cache")
+                .long("no-cache")
+                .help("Format every file instead of skipping files the cache knows are formatted")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("cache-location")
+                .long("cache-location")
+                .help("Directory of the cache [default: node_modules/.cache/biome-tailwind-sorter]")
+                .value_name("DIR")
+                .conflicts_with("no-cache")
+        )
+        .arg(
+            Arg::new("remove-duplicates")
+                .long("remove-duplicates")
+                .help("Remove duplicate classes")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("fix-shorthands")
+                .long("fix-shorthands")
+                .help("Collapse class pairs into shorthands (e.g. mt-2 mb-2 into my-2)")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("normalize-whitespace")
+                .long("normalize-whitespace")
+                .help("Collapse extra whitespace in class attributes, even when already sorted")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("layout")
+                .long("layout")
+                .help("Layout of multiline class attributes")
+                .value_name("LAYOUT")
+                .value_parser(["preserve", "one-per-line", "group-per-line", "wrap"])
+        )
+        .arg(
+            Arg::new("print-width")
+                .long("print-width")
+                .help("Maximum line width for the wrap layout")
+                .value_name("WIDTH")
+                .value_parser(clap::value_parser!(usize))
+        )
+        .arg(
+            Arg::new("range")
+                .long("range")
+                .help("Only format class attributes overlapping this byte range (end exclusive)")
+                .value_name("START:END")
+                .value_parser(parse_byte_range)
+                .conflicts_with_all(["lines", "lint", "staged", "since"])
+        )
+        .arg(
+            Arg::new("lines")
+                .long("lines")
+                .help("Only format class attributes overlapping these lines (1-based, inclusive)")
+                .value_name("FIRST-LAST")
+                .value_parser(parse_line_range)
+                .conflicts_with_all(["lint", "staged", "since"])
+        )
         .arg(
             Arg::new("preserve-cursor")
                 .long("preserve-cursor")
@@ -68,15 +252,117 @@ fn main() {
                 .value_name("OFFSET")
                 .value_parser(clap::value_parser!(usize))
         )
+        .arg(
+            Arg::new("cursor-encoding")
+                .long("cursor-encoding")
+                .help("Unit of the cursor offset and of the reported cursor column and offset")
+                .value_name("ENCODING")
+                .value_parser(["utf8", "utf16"])
+                .default_value("utf8")
+        )
+        .subcommand(
+            Command::new("print-schema")
+                .about("Print the JSON Schema of the configuration file")
+        )
+        .subcommand(
+            Command::new("init")
+                .about("Detect the project setup and write a starter configuration file")
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .help("Format of the configuration file")
+                        .value_name("FORMAT")
+                        .value_parser(["json", "toml"])
+                        .default_value("json")
+                )
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .help("Overwrite an existing configuration file")
+                        .action(clap::ArgAction::SetTrue)
+                )
+        )
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
         .get_matches();
 
-    let files: Vec<&String> = matches.get_many::<String>("files").unwrap().collect();
+    if let Some(("print-schema", _)) = matches.subcommand() {
+        match serde_json::to_string_pretty(&Config::json_schema()) {
+            Ok(schema) => println!("{schema}"),
+            Err(err) => {
+                eprintln!("Error: {err}");
+                process::exit(2);
+            }
+        }
+        return;
+    }
+    if let Some(("init", init_matches)) = matches.subcommand() {
+        // Without --format, an existing config keeps its format
+        let toml = match init_matches.value_source("format") {
+            Some(ValueSource::CommandLine) => {
+                Some(init_matches.get_one::<String>("format").map(String::as_str) == Some("toml"))
+            }
+            _ => None,
+        };
+        if let Err(err) = init(toml, init_matches.get_flag("force")) {
+            eprintln!("Error: {err}");
+            process::exit(2);
+        }
+        return;
+    }
+
+    let files: Vec<&String> = matches
+        .get_many::<String>("files")
+        .map(|files| files.collect())
+        .unwrap_or_default();
     let write = matches.get_flag("write");
     let check = matches.get_flag("check");
+    let lint = matches.get_flag("lint");
+    let diff = matches.get_flag("diff").then(diff::use_color);
     let verbose = matches.get_flag("verbose");
     let preserve_cursor = matches.get_flag("preserve-cursor");
+    let stdin = matches.get_flag("stdin");
+    let staged = matches.get_flag("staged");
+    let watch = matches.get_flag("watch");
+    let since = matches.get_one::<String>("since");
+    let range = matches
+        .get_one::<FormatRange>("range")
+        .or_else(|| matches.get_one::<FormatRange>("lines"));
+    let reporter_name = matches.get_one::<String>("reporter").map_or("text", String::as_str);
+    let threads = matches
+        .get_one::<NonZeroUsize>("threads")
+        .copied()
+        .or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get);
+
+    let resolver = match matches.get_one::<String>("config") {
+        Some(path) => ConfigResolver::from_file(path),
+        None => Ok(ConfigResolver::new()),
+    };
+    let mut resolver = resolver.unwrap_or_else(|err| exit_with_error(err.into()));
+    let (config, config_source) = resolver
+        .resolve_dir(Path::new("."))
+        .unwrap_or_else(|err| exit_with_error(err.into()));
+    
+    // With --stdin or a machine-readable reporter, stdout only carries that output
+    if verbose {
+        let message = match &config_source {
+            Some(path) => format!("Using config {}", path.display()),
+            None => "No config file found, using defaults".to_string(),
+        };
+        if stdin || reporter_name != "text" {
+            eprintln!("{message}");
+        } else {
+            println!("{message}");
+        }
+    }
+    let config = with_cli_overrides(&config, &matches);
 
     // Parse cursor position if provided
+    let cursor_encoding = match matches.get_one::<String>("cursor-encoding").map(String::as_str) {
+        Some("utf16") => CursorEncoding::Utf16,
+        _ => CursorEncoding::Utf8,
+    };
     let cursor_position = if preserve_cursor {
         let line = matches.get_one::<usize>("cursor-line").copied();
         let column = matches.get_one::<usize>("cursor-column").copied();
@@ -95,134 +381,854 @@ fn main() {
             }),
             _ => None,
         }
+        .map(|position| (position, cursor_encoding))
     } else {
         None
     };
 
-    let expanded_files = get_files(&files);
+    if stdin {
+        let mut formatters = Formatters::new(&matches, preserve_cursor);
+        let file_path = matches.get_one::<String>("stdin-filepath").map(String::as_str);
+        match format_stdin(&mut resolver, &mut formatters, file_path, check, lint, range, cursor_position) {
+            Ok(code) => process::exit(code),
+            Err(err) => {
+                eprintln!("✗ Error processing stdin: {}", err.render());
+                process::exit(err.exit_code());
+            }
+        }
+    }
+
+    // With --staged or --since, git lists the files and FILES are pathspecs
+    let git_files = match since {
+        _ if staged => Some(git::staged_files(&files)),
+        Some(since) => Some(git::changed_files(since, &files)),
+        None => None,
+    };
+    let from_git = git_files.is_some();
+    let candidates = match git_files {
+        Some(Ok(git_files)) => git_files,
+        Some(Err(err)) => exit_with_error(err),
+        None => get_files(&files, &config),
+    };
+    
+    // Each file uses the config nearest to it, which may exclude it
+    let mut formatters = Formatters::new(&matches, preserve_cursor);
+    let mut expanded_files = Vec::new();
+    let mut file_formatters = Vec::new();
+    for file_path in candidates {
+        match select_file(&mut resolver, &mut formatters, &file_path) {
+            Ok(Some(index)) => {
+                expanded_files.push(file_path);
+                file_formatters.push(index);
+            }
+            Ok(None) => {}
+            Err(err) => exit_with_error(err.into()),
+        }
+    }
     
+    // Nothing staged or changed is fine, e.g. in a pre-commit hook
+    if expanded_files.is_empty() && from_git {
+        if verbose {
+            println!("No {} files to process", if staged { "staged" } else { "changed" });
+        }
+        return;
+    }
     if expanded_files.is_empty() {
         eprintln!("Error: No supported files found in the specified paths.");
-        eprintln!("Supported extensions: .js, .jsx, .ts, .tsx, .html, .vue, .astro");
+        eprintln!(
+            "Supported extensions: {}",
+            config.extensions.iter().map(|ext| format!(".{ext}")).collect::<Vec<_>>().join(", ")
+        );
         eprintln!("Try specifying a directory or file with supported extensions.");
         process::exit(1);
     }
 
-    let formatter = TailwindFormatter::new(preserve_cursor);
-    let mut changed_files = 0;
-    let mut error_files = 0;
-
-    for file_path in &expanded_files {
-        match process_file(&formatter, file_path, write, verbose, cursor_position.clone()) {
-            Ok(changed) => {
-                if changed {
-                    changed_files += 1;
+    let built = &formatters.built;
+    let cache_dir = match matches.get_one::<String>("cache-location") {
+        _ if matches.get_flag("no-cache") => None,
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => match &config.cache_dir {
+            Some(dir) => Some(config_source.as_ref().and_then(|path| path.parent()).unwrap_or(Path::new(".")).join(dir)),
+            // Only projects with node_modules get a cache by default
+            None => Path::new("node_modules").is_dir().then(|| PathBuf::from(DEFAULT_CACHE_DIR)),
+        },
+    };
+    if lint {
+        let mut error_code = None;
+        let mut diagnostic_count = 0;
+        for_each_parallel(
+            expanded_files.len(),
+            threads,
+            |index| {
+                let (formatter, read, _) = &built[file_formatters[index]];
+                lint_file(formatter, &expanded_files[index], *read)
+            },
+            |index, diagnostics| {
+                let file_path = &expanded_files[index];
+                match diagnostics {
+                    Ok(diagnostics) => {
+                        for diagnostic in &diagnostics {
+                            println!("{file_path}:{}:{}: {}", diagnostic.line, diagnostic.column, diagnostic.message);
+                        }
+                        diagnostic_count += diagnostics.len();
+                    }
+                    Err(err) => {
+                        eprintln!("✗ Error processing {file_path}: {}", err.render());
+                        error_code.get_or_insert(err.exit_code());
+                    }
                 }
+            },
+        );
+
+        if verbose || diagnostic_count > 0 {
+            println!("\nFound {diagnostic_count} problems in {} files", expanded_files.len());
+        }
+
+        if let Some(code) = error_code {
+            process::exit(code);
+        } else if diagnostic_count > 0 {
+            process::exit(1);
+        }
+        return;
+    }
+
+    // The cache describes whole working tree files
+    let mut cache = cache_dir.filter(|_| !staged && range.is_none()).map(|dir| Cache::load(&dir));
+    let persist_cache = cache.is_some();
+    if watch {
+        // Tells the watcher which files it formatted, so their own change
+        // events are ignored
+        cache.get_or_insert_with(Cache::default);
+    }
+    let mut cache_updates = Vec::new();
+    let mut cache_hits = 0;
+    let mut error_code = None;
+    let mut reporter = report::reporter(reporter_name, write, verbose);
+    let mut summary = Summary::new(write);
+    for_each_parallel(
+        expanded_files.len(),
+        threads,
+        |index| {
+            let (formatter, read, config_hash) = &built[file_formatters[index]];
+            let cache = cache.as_ref().map(|cache| (cache, *config_hash));
+            if staged {
+                process_staged_file(formatter, &expanded_files[index], write, *read)
+            } else {
+                process_file(formatter, &expanded_files[index], write, range, cursor_position.clone(), *read, cache)
             }
-            Err(err) => {
-                eprintln!("✗ Error processing {file_path}: {err}");
-                if let Some(source) = err.source() {
-                    eprintln!("   Caused by: {source}");
+        },
+        |index, processed| {
+            let file_path = &expanded_files[index];
+            let record = match processed {
+                Ok(processed) => {
+                    let result = &processed.result;
+                    if let (true, Some(color)) = (result.changed, diff) {
+                        print!("{}", diff::unified_diff(file_path, &processed.source.text, &result.content, color));
+                    }
+                    // Written to stderr so it doesn't interfere with file content
+                    if let (true, Some(cursor)) = (write && result.changed, &result.cursor_position) {
+                        eprintln!("CURSOR_POSITION:{}:{}:{}", cursor.line, cursor.column, cursor.offset);
+                    }
+                    if processed.partially_staged && result.changed && write {
+                        eprintln!("Note: {file_path} has unstaged changes, only its staged content was formatted");
+                    }
+                    cache_hits += usize::from(processed.cached);
+                    cache_updates.push((index, processed.formatted_key));
+                    FileRecord::from_result(file_path, &processed.source.text, result).in_file(&processed.source)
+                }
+                Err(err) => {
+                    cache_updates.push((index, None));
+                    error_code.get_or_insert(err.exit_code());
+                    FileRecord::failed(file_path, err.render())
                 }
-                error_files += 1;
+            };
+            summary.add(&record);
+            reporter.file(&record);
+        },
+    );
+    reporter.finish(&summary);
+    
+    if let Some(cache) = &mut cache {
+        for (index, key) in cache_updates {
+            let path = Path::new(&expanded_files[index]);
+            match key {
+                Some(key) => cache.insert(path, key),
+                None => cache.remove(path),
+            }
+        }
+        // Without a cache directory, --watch keeps it in memory
+        if persist_cache {
+            if let Err(err) = cache.save() {
+                eprintln!("Warning: Failed to save the cache: {err}");
+            }
+        }
+        if verbose && persist_cache {
+            let message = format!("Cache: {cache_hits} of {} files unchanged since they were last formatted", summary.files);
+            if reporter_name == "text" {
+                println!("{message}");
+            } else {
+                eprintln!("{message}");
             }
         }
     }
 
-    // Summary
-    if verbose || !write {
-        println!("\nProcessed {} files:", expanded_files.len());
-        println!("  {} files {}", changed_files, if write { "formatted" } else { "need formatting" });
-        println!("  {} files already formatted", expanded_files.len() - changed_files - error_files);
-        
-        if error_files > 0 {
-            println!("  {error_files} files had errors");
+    if let (true, Some(cache)) = (watch, &mut cache) {
+        let session = WatchSession { write, diff, persist_cache };
+        if let Err(err) = watch_files(&files, &config, &mut resolver, &mut formatters, cache, &session) {
+            eprintln!("Error: {err}");
+            process::exit(2);
         }
+        return;
     }
 
     // Exit codes
-    if error_files > 0 {
-        process::exit(2); // Errors occurred
-    } else if check && changed_files > 0 {
+    if let Some(code) = error_code {
+        process::exit(code); // Errors occurred, by the first failed file
+    } else if check && summary.changed > 0 {
         process::exit(1); // Files need formatting
     }
 }
 
+/// Print an error that stops the run and exit with its code
+fn exit_with_error(err: SorterError) -> ! {
+    eprintln!("Error: {}", err.render());
+    process::exit(err.exit_code());
+}
+
+/// Settings of a `--watch` session
+struct WatchSession {
+    write: bool,
+    diff: Option<bool>,
+    /// Whether the cache is saved after each batch of changes
+    persist_cache: bool,
+}
+
+/// Process the files matching the command line arguments again whenever
+/// they change, until interrupted. Files the cache knows are formatted,
+/// including the ones just written, are skipped.
+fn watch_files(
+    args: &[&String],
+    config: &Config,
+    resolver: &mut ConfigResolver,
+    formatters: &mut Formatters,
+    cache: &mut Cache,
+    session: &WatchSession,
+) -> Result<(), String> {
+    let roots: Vec<PathBuf> = args
+        .iter()
+        .map(|arg| match Path::new(arg.as_str()) {
+            path if !path.exists() && is_glob(arg) => glob_base(arg),
+            path => path.to_path_buf(),
+        })
+        .collect();
+    let watcher = watch::FileWatcher::new(&roots)?;
+    if watcher.polling {
+        eprintln!("File notifications are unavailable, polling for changes");
+    }
+    println!("Watching for changes (press Ctrl+C to stop)...");
+    
+    // Files matching the arguments by canonical path, refreshed when an
+    // unknown file changes, e.g. a new one
+    let mut known = HashMap::new();
+    let refresh = |known: &mut HashMap<PathBuf, String>| {
+        known.clear();
+        for file in get_files(args, config) {
+            if let Ok(path) = fs::canonicalize(&file) {
+                known.insert(path, file);
+            }
+        }
+    };
+    refresh(&mut known);
+    
+    while let Some(paths) = watcher.next_batch(watch::DEBOUNCE) {
+        let mut refreshed = false;
+        for path in paths {
+            let Ok(path) = fs::canonicalize(&path) else {
+                continue;
+            };
+            if !known.contains_key(&path)
+                && !refreshed
+                && matches!(select_file(resolver, formatters, &path.to_string_lossy()), Ok(Some(_)))
+            {
+                refresh(&mut known);
+                refreshed = true;
+            }
+            let Some(file_path) = known.get(&path) else {
+                continue;
+            };
+            
+            let index = match select_file(resolver, formatters, file_path) {
+                Ok(Some(index)) => index,
+                Ok(None) => continue,
+                Err(err) => {
+                    eprintln!("Error: {}", SorterError::from(err).render());
+                    continue;
+                }
+            };
+            let (formatter, read, config_hash) = &formatters.built[index];
+            match process_file(formatter, file_path, session.write, None, None, *read, Some((cache, *config_hash))) {
+                Ok(processed) if processed.cached => {}
+                Ok(processed) => {
+                    let result = &processed.result;
+                    if let (true, Some(color)) = (result.changed, session.diff) {
+                        print!("{}", diff::unified_diff(file_path, &processed.source.text, &result.content, color));
+                    }
+                    if result.changed && session.write {
+                        println!("✓ Formatted {file_path}");
+                    } else if result.changed {
+                        println!("⚠ {file_path} needs formatting");
+                    }
+                    match processed.formatted_key {
+                        Some(key) => cache.insert(Path::new(file_path), key),
+                        None => cache.remove(Path::new(file_path)),
+                    }
+                }
+                Err(err) => eprintln!("✗ Error processing {file_path}: {}", err.render()),
+            }
+        }
+        
+        if session.persist_cache {
+            if let Err(err) = cache.save() {
+                eprintln!("Warning: Failed to save the cache: {err}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The index in `formatters` for a file, or `None` when the config
+/// resolved for it, with `--include` and `--exclude`, excludes the file by
+/// extension, `include` or `ignore`. Nested configs thus apply their own
+/// lists to the files below them, and explicitly named files are filtered
+/// like any other.
+fn select_file(
+    resolver: &mut ConfigResolver,
+    formatters: &mut Formatters,
+    file_path: &str,
+) -> Result<Option<usize>, config::ConfigError> {
+    let config = resolver.resolve(Path::new(file_path))?;
+    let index = formatters.index(&config);
+    let effective = &formatters.effective[index];
+    let selected = effective.should_process_file(file_path) && !effective.should_ignore_path(file_path);
+    Ok(selected.then_some(index))
+}
+
+/// Write a starter config for the project in the current directory,
+/// printing what was detected. `toml` is the format asked for, if any;
+/// with `force`, a config in another format is replaced.
+fn init(toml: Option<bool>, force: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let info = ProjectInfo::detect(Path::new("."));
+    if let Some(existing) = &info.existing_config {
+        if !force {
+            return Err(format!(
+                "{} already exists (use --force to overwrite)",
+                existing.display()
+            ).into());
+        }
+    }
+    
+    println!("Detected:");
+    for line in info.summary() {
+        println!("  {line}");
+    }
+    
+    let is_toml = |path: &Path| path.extension().is_some_and(|ext| ext == "toml");
+    let path = match (&info.existing_config, toml) {
+        (Some(existing), None) => existing.clone(),
+        (Some(existing), Some(toml)) if is_toml(existing) == toml => existing.clone(),
+        (_, Some(true)) => PathBuf::from("tailwindsorter.toml"),
+        (_, _) => PathBuf::from("tailwindsorter.config.json"),
+    };
+    // TOML files have no `$schema` convention
+    info.starter_config(!is_toml(&path)).save_to_file(&path)?;
+    println!("Created {}", path.display());
+    
+    // Only the first config file of a directory is read, which could be the old one
+    if let Some(existing) = info.existing_config.filter(|existing| *existing != path) {
+        fs::remove_file(&existing)?;
+        println!("Removed {}", existing.display());
+    }
+    Ok(())
+}
+
+/// A copy of `config` with the command line flags applied, which take
+/// precedence over the config file
+fn with_cli_overrides(config: &Config, matches: &ArgMatches) -> Config {
+    let mut config = config.clone();
+    let include: Vec<String> = matches.get_many::<String>("include").into_iter().flatten().cloned().collect();
+    let exclude: Vec<String> = matches.get_many::<String>("exclude").into_iter().flatten().cloned().collect();
+    config.extend_patterns(&include, &exclude);
+    config.remove_duplicates |= matches.get_flag("remove-duplicates");
+    config.fix_shorthands |= matches.get_flag("fix-shorthands");
+    config.normalize_whitespace |= matches.get_flag("normalize-whitespace");
+    match matches.get_one::<String>("layout").map(String::as_str) {
+        Some("one-per-line") => config.multiline_layout = MultilineLayout::OnePerLine,
+        Some("group-per-line") => config.multiline_layout = MultilineLayout::GroupPerLine,
+        Some("wrap") => config.multiline_layout = MultilineLayout::Wrap,
+        Some(_) => config.multiline_layout = MultilineLayout::Preserve,
+        None => {}
+    }
+    if let Some(&print_width) = matches.get_one::<usize>("print-width") {
+        config.print_width = print_width;
+    }
+    config
+}
+
+/// Formatters for each distinct resolved config, built on first use
+struct Formatters<'a> {
+    matches: &'a ArgMatches,
+    preserve_cursor: bool,
+    configs: Vec<Rc<Config>>,
+    /// Each config with the command line flags applied, in the order of `configs`
+    effective: Vec<Config>,
+    /// Formatter, read options and config hash, in the order of `configs`
+    built: Vec<(TailwindFormatter, ReadOptions, u64)>,
+}
+
+impl<'a> Formatters<'a> {
+    fn new(matches: &'a ArgMatches, preserve_cursor: bool) -> Self {
+        Self { matches, preserve_cursor, configs: Vec::new(), effective: Vec::new(), built: Vec::new() }
+    }
+    
+    /// Index in `built` of the formatter for a resolved config
+    fn index(&mut self, config: &Rc<Config>) -> usize {
+        match self.configs.iter().position(|built| Rc::ptr_eq(built, config)) {
+            Some(index) => index,
+            None => {
+                let effective = with_cli_overrides(config, self.matches);
+                let formatter = TailwindFormatter::from_config(self.preserve_cursor, &effective);
+                self.configs.push(config.clone());
+                let read = ReadOptions {
+                    max_file_size: effective.max_file_size as u64,
+                    encoding: self.matches.get_one::<&'static Encoding>("encoding").copied().unwrap_or(encoding_rs::UTF_8),
+                };
+                self.built.push((formatter, read, Cache::config_hash(&effective)));
+                self.effective.push(effective);
+                self.built.len() - 1
+            }
+        }
+    }
+    
+    /// The formatter and read options for a resolved config
+    fn get(&mut self, config: &Rc<Config>) -> (&TailwindFormatter, ReadOptions) {
+        let index = self.index(config);
+        let (formatter, read, _) = &self.built[index];
+        (formatter, *read)
+    }
+}
+
+/// Run `job` for the indices `0..count` on up to `threads` threads, passing
+/// each result to `emit` on the calling thread in index order
+fn for_each_parallel<T: Send>(
+    count: usize,
+    threads: usize,
+    job: impl Fn(usize) -> T + Sync,
+    mut emit: impl FnMut(usize, T),
+) {
+    let threads = threads.min(count);
+    if threads <= 1 {
+        (0..count).for_each(|index| emit(index, job(index)));
+        return;
+    }
+    
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            let sender = sender.clone();
+            let (next, job) = (&next, &job);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                if index >= count || sender.send((index, job(index))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+        
+        // Results arrive in any order; hold them until their turn
+        let mut pending: Vec<Option<T>> = (0..count).map(|_| None).collect();
+        let mut emitted = 0;
+        for (index, result) in receiver {
+            pending[index] = Some(result);
+            while let Some(result) = pending.get_mut(emitted).and_then(Option::take) {
+                emit(emitted, result);
+                emitted += 1;
+            }
+        }
+    });
+}
+
+/// Part of each document to format, from `--range` or `--lines`
+#[derive(Debug, Clone, PartialEq)]
+enum FormatRange {
+    /// Byte offsets, end exclusive
+    Bytes(usize, usize),
+    /// 1-based lines, inclusive
+    Lines(usize, usize),
+}
+
+impl FormatRange {
+    /// The range in the decoded text of `source`, as `--range` counts bytes
+    /// of the file
+    fn in_text(&self, source: &SourceText) -> FormatRange {
+        match *self {
+            FormatRange::Bytes(start, end) => FormatRange::Bytes(source.text_offset(start), source.text_offset(end)),
+            FormatRange::Lines(first, last) => FormatRange::Lines(first, last),
+        }
+    }
+
+    /// Byte range of the part of `source` to format
+    fn offsets(&self, source: &str) -> Range<usize> {
+        match *self {
+            FormatRange::Bytes(start, end) => start.min(source.len())..end.min(source.len()),
+            FormatRange::Lines(first, last) => formatter::line_range(source, first, last),
+        }
+    }
+}
+
+/// Format `source`, or only the part of it in `range`
+fn format_source(
+    formatter: &TailwindFormatter,
+    source: &str,
+    range: Option<&FormatRange>,
+    cursor_position: Option<(CursorPosition, CursorEncoding)>,
+) -> FormatResult {
+    let (cursor_position, encoding) = match cursor_position {
+        Some((position, encoding)) => {
+            let offset = encoding.byte_offset(source, position.offset);
+            (Some(CursorPosition { offset, ..position }), encoding)
+        }
+        None => (None, CursorEncoding::Utf8),
+    };
+    let mut result = match range {
+        Some(range) => formatter.format_range(source, range.offsets(source), cursor_position),
+        None => formatter.format_document(source, cursor_position),
+    };
+    
+    // Report the cursor in the unit it was given in
+    if let Some(cursor) = &mut result.cursor_position {
+        let line_start = result.content[..cursor.offset].rfind('\n').map_or(0, |index| index + 1);
+        cursor.column = encoding.unit_offset(&result.content[line_start..], cursor.offset - line_start);
+        cursor.offset = encoding.unit_offset(&result.content, cursor.offset);
+    }
+    result
+}
+
+/// Unit of `--cursor-offset` and of the reported cursor column and offset.
+/// Editors like VS Code count UTF-16 code units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CursorEncoding {
+    Utf8,
+    Utf16,
+}
+
+impl CursorEncoding {
+    /// Byte offset in `source` of `offset` in this unit, at the start of the
+    /// character it falls in
+    fn byte_offset(self, source: &str, offset: usize) -> usize {
+        match self {
+            CursorEncoding::Utf8 => offset,
+            CursorEncoding::Utf16 => source
+                .char_indices()
+                .scan(0, |units, (index, ch)| {
+                    *units += ch.len_utf16();
+                    Some((index, *units))
+                })
+                .find(|&(_, end)| end > offset)
+                .map_or(source.len(), |(index, _)| index),
+        }
+    }
+
+    /// `offset`, a byte offset at a char boundary of `text`, in this unit
+    fn unit_offset(self, text: &str, offset: usize) -> usize {
+        match self {
+            CursorEncoding::Utf8 => offset,
+            CursorEncoding::Utf16 => text[..offset].encode_utf16().count(),
+        }
+    }
+}
+
+/// How source files are read
+#[derive(Debug, Clone, Copy)]
+struct ReadOptions {
+    max_file_size: u64,
+    /// Encoding of files without a byte order mark
+    encoding: &'static Encoding,
+}
+
+/// A file processed by `process_file`
+#[derive(Debug)]
+struct Processed {
+    /// The file as read
+    source: SourceText,
+    result: FormatResult,
+    /// Formatting was skipped because the cache knows the content is formatted
+    cached: bool,
+    /// Cache key of the file content after this run, if it is formatted
+    formatted_key: Option<u64>,
+    /// With `--staged`, the working tree copy has unstaged changes
+    partially_staged: bool,
+}
+
+/// Format a file (or the part of it in `range`), writing it back with
+/// `write`. With a cache and the hash of the formatter's config, files the
+/// cache knows are formatted are skipped.
 fn process_file(
     formatter: &TailwindFormatter,
     file_path: &str,
     write: bool,
-    verbose: bool,
-    cursor_position: Option<CursorPosition>,
-) -> Result<bool, Box<dyn std::error::Error>> {
-    // Validate file exists and is readable
-    let metadata = fs::metadata(file_path)
-        .map_err(|e| format!("Cannot access file '{file_path}': {e}"))?;
+    range: Option<&FormatRange>,
+    cursor_position: Option<(CursorPosition, CursorEncoding)>,
+    read: ReadOptions,
+    cache: Option<(&Cache, u64)>,
+) -> Result<Processed, SorterError> {
+    let source = read_source(file_path, read)?;
+    let key = cache.map(|(_, config_hash)| Cache::key(&source.text, config_hash));
+    if let (Some((cache, _)), Some(key)) = (cache, key) {
+        if cache.is_formatted(Path::new(file_path), key) {
+            let result = FormatResult {
+                content: source.text.clone(),
+                cursor_position: None,
+                changed: false,
+                duplicates_removed: 0,
+                shorthands_collapsed: 0,
+                changes: Vec::new(),
+            };
+            return Ok(Processed {
+                source,
+                result,
+                cached: true,
+                formatted_key: Some(key),
+                partially_staged: false,
+            });
+        }
+    }
     
-    if !metadata.is_file() {
-        return Err(format!("'{file_path}' is not a regular file").into());
+    let range = range.map(|range| range.in_text(&source));
+    let result = format_source(formatter, &source.text, range.as_ref(), cursor_position);
+    if result.changed && write {
+        fs::write(file_path, source.encode(&result.content))
+            .map_err(|err| SorterError::io(file_path, "write to", err))?;
     }
     
-    // Check file size (prevent processing very large files)
-    const MAX_FILE_SIZE: u64 = 50 * 1024 * 1024; // 50MB
-    if metadata.len() > MAX_FILE_SIZE {
-        return Err(format!("File '{}' is too large ({} bytes). Maximum size is {} bytes.", 
-                          file_path, metadata.len(), MAX_FILE_SIZE).into());
+    let formatted_key = match cache {
+        Some(_) if !result.changed => key,
+        Some((_, config_hash)) if write => Some(Cache::key(&result.content, config_hash)),
+        _ => None,
+    };
+    Ok(Processed { source, result, cached: false, formatted_key, partially_staged: false })
+}
+
+/// Format the staged content of a file. With `write` the result is staged,
+/// and the working tree copy is formatted as well unless it has unstaged
+/// changes, which are left untouched.
+fn process_staged_file(
+    formatter: &TailwindFormatter,
+    file_path: &str,
+    write: bool,
+    read: ReadOptions,
+) -> Result<Processed, SorterError> {
+    let staged = git::read_staged(file_path)?;
+    let source = decode_source(file_path, staged.content.clone(), read)?;
+    let result = formatter.format_document(&source.text, None);
+    
+    let mut partially_staged = false;
+    if result.changed && write {
+        // Checked before restaging, which would make the file look modified
+        partially_staged = git::has_unstaged_changes(file_path)?;
+        git::stage(&staged, &source.encode(&result.content))?;
+        if !partially_staged {
+            process_file(formatter, file_path, true, None, None, read, None)?;
+        }
     }
     
-    let content = fs::read_to_string(file_path)
-        .map_err(|e| format!("Failed to read file '{file_path}': {e}"))?;
-    let result = formatter.format_document(&content, cursor_position);
+    Ok(Processed { source, result, cached: false, formatted_key: None, partially_staged })
+}
+
+/// Format source read from stdin, writing the result to stdout, and return
+/// the exit code. `file_path` picks the config and whether the file type
+/// is supported; unsupported sources are written back unchanged.
+fn format_stdin(
+    resolver: &mut ConfigResolver,
+    formatters: &mut Formatters,
+    file_path: Option<&str>,
+    check: bool,
+    lint: bool,
+    range: Option<&FormatRange>,
+    cursor_position: Option<(CursorPosition, CursorEncoding)>,
+) -> Result<i32, SorterError> {
+    let config = match file_path {
+        Some(path) => resolver.resolve(Path::new(path))?,
+        None => resolver.resolve_dir(Path::new("."))?.0,
+    };
+    let (formatter, read) = formatters.get(&config);
     
-    if result.changed {
-        if write {
-            // Create backup before writing (optional safety measure)
-            fs::write(file_path, &result.content)
-                .map_err(|e| format!("Failed to write to file '{file_path}': {e}"))?;
-            
-            // Output cursor position if requested and available
-            if let Some(cursor) = result.cursor_position {
-                // Write cursor position to stderr so it doesn't interfere with file content
-                eprintln!("CURSOR_POSITION:{}:{}:{}", cursor.line, cursor.column, cursor.offset);
-            }
-            
-            if verbose {
-                println!("✓ Formatted {file_path}");
-            }
-        } else if verbose {
-            println!("⚠ {file_path} needs formatting");
+    let mut source = Vec::new();
+    io::stdin()
+        .read_to_end(&mut source)
+        .map_err(|err| SorterError::io("<stdin>", "read", err))?;
+    let input = decode_source(file_path.unwrap_or("<stdin>"), source, read)?;
+    let source = input.text.as_str();
+    let write_error = |err| SorterError::io("<stdout>", "write to", err);
+    let mut stdout = io::stdout().lock();
+    
+    if file_path.is_some_and(|path| !config.should_process_file(path)) {
+        if !lint {
+            stdout.write_all(&input.encode(source)).map_err(write_error)?;
+        }
+        return Ok(0);
+    }
+    
+    if lint {
+        let diagnostics = formatter.lint_document(source);
+        for diagnostic in &diagnostics {
+            writeln!(
+                stdout,
+                "{}:{}:{}: {}",
+                file_path.unwrap_or("<stdin>"),
+                diagnostic.line,
+                diagnostic.column,
+                diagnostic.message
+            )
+            .map_err(write_error)?;
         }
-    } else if verbose {
-        println!("✓ {file_path} is already formatted");
+        return Ok(if diagnostics.is_empty() { 0 } else { 1 });
+    }
+    
+    let range = range.map(|range| range.in_text(&input));
+    let result = format_source(formatter, source, range.as_ref(), cursor_position);
+    stdout.write_all(&input.encode(&result.content)).map_err(write_error)?;
+    stdout.flush().map_err(write_error)?;
+    if let Some(cursor) = result.cursor_position {
+        eprintln!("CURSOR_POSITION:{}:{}:{}", cursor.line, cursor.column, cursor.offset);
+    }
+    
+    Ok(if check && result.changed { 1 } else { 0 })
+}
+
+fn lint_file(
+    formatter: &TailwindFormatter,
+    file_path: &str,
+    read: ReadOptions,
+) -> Result<Vec<LintDiagnostic>, SorterError> {
+    let source = read_source(file_path, read)?;
+    Ok(formatter.lint_document(&source.text))
+}
+
+fn read_source(file_path: &str, read: ReadOptions) -> Result<SourceText, SorterError> {
+    // Validate file exists and is readable
+    let metadata = fs::metadata(file_path).map_err(|err| SorterError::io(file_path, "access", err))?;
+    
+    if !metadata.is_file() {
+        let err = io::Error::new(io::ErrorKind::InvalidInput, "not a regular file");
+        return Err(SorterError::io(file_path, "read", err));
     }
     
-    Ok(result.changed)
+    // Check file size (prevent processing very large files)
+    if metadata.len() > read.max_file_size {
+        return Err(SorterError::TooLarge { path: file_path.into(), size: metadata.len(), max_size: read.max_file_size });
+    }
+    
+    let content = fs::read(file_path).map_err(|err| SorterError::io(file_path, "read", err))?;
+    decode_source(file_path, content, read)
+}
+
+/// Check the size of source read from a file, the index or stdin, and
+/// decode it
+fn decode_source(path: &str, content: Vec<u8>, read: ReadOptions) -> Result<SourceText, SorterError> {
+    if content.len() as u64 > read.max_file_size {
+        return Err(SorterError::TooLarge { path: path.into(), size: content.len() as u64, max_size: read.max_file_size });
+    }
+    SourceText::decode(Path::new(path), content, read.encoding)
+}
+
+/// Parse a `--range` value like `120:480`
+fn parse_byte_range(value: &str) -> Result<FormatRange, String> {
+    let (start, end) = value.split_once(':').ok_or("expected START:END")?;
+    let start: usize = start.trim().parse().map_err(|_| format!("invalid start offset `{start}`"))?;
+    let end: usize = end.trim().parse().map_err(|_| format!("invalid end offset `{end}`"))?;
+    if start > end {
+        return Err("the start offset is after the end offset".to_string());
+    }
+    Ok(FormatRange::Bytes(start, end))
+}
+
+/// Parse a `--lines` value like `10-40`, or a single line like `12`
+fn parse_line_range(value: &str) -> Result<FormatRange, String> {
+    let (first, last) = value.split_once('-').unwrap_or((value, value));
+    let first: usize = first.trim().parse().map_err(|_| format!("invalid line `{first}`"))?;
+    let last: usize = last.trim().parse().map_err(|_| format!("invalid line `{last}`"))?;
+    if first == 0 || first > last {
+        return Err("expected FIRST-LAST with 1 <= FIRST <= LAST".to_string());
+    }
+    Ok(FormatRange::Lines(first, last))
 }
 
-fn get_files(patterns: &[&String]) -> Vec<String> {
+/// Check that an `--include` or `--exclude` glob is valid, compiled like
+/// the config's patterns
+fn parse_glob(pattern: &str) -> Result<String, String> {
+    PathPatterns::validate(pattern).map(|_| pattern.to_string())
+}
+
+/// Whether a command line argument is a glob pattern rather than a path
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '[', '{'])
+}
+
+/// The directory to walk for a glob: its leading components without glob
+/// characters
+fn glob_base(pattern: &str) -> PathBuf {
+    let base: PathBuf = Path::new(pattern)
+        .components()
+        .take_while(|component| !is_glob(&component.as_os_str().to_string_lossy()))
+        .collect();
+    if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        base
+    }
+}
+
+/// A path without `.` components, for matching against globs
+fn normalize_path(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| !matches!(component, std::path::Component::CurDir))
+        .collect()
+}
+
+/// Expand the command line arguments into the candidate files. Arguments
+/// are files, directories or glob patterns, which are expanded internally
+/// so they work without shell support. Each candidate is then checked
+/// against its own config, see [`select_file`].
+fn get_files(patterns: &[&String], config: &Config) -> Vec<String> {
     let mut files = Vec::new();
     
     for pattern in patterns {
         match fs::metadata(pattern) {
             Ok(metadata) => {
                 if metadata.is_file() {
-                    if should_process_file(pattern) {
-                        files.push(pattern.to_string());
-                    }
+                    files.push(pattern.to_string());
                 } else if metadata.is_dir() {
-                    // Simple directory traversal
-                    if let Ok(entries) = fs::read_dir(pattern) {
-                        for entry in entries.flatten() {
-                            let path = entry.path();
-                            if path.is_file() {
-                                if let Some(path_str) = path.to_str() {
-                                    if should_process_file(path_str) {
-                                        files.push(path_str.to_string());
-                                    }
-                                }
-                            }
-                        }
+                    collect_dir_files(Path::new(pattern.as_str()), config, config.recursive, None, &mut files);
+                }
+            }
+            Err(_) if is_glob(pattern) => {
+                let glob = GlobBuilder::new(&normalize_path(Path::new(pattern.as_str())).to_string_lossy())
+                    .literal_separator(true)
+                    .build();
+                let matcher = match glob {
+                    Ok(glob) => glob.compile_matcher(),
+                    Err(err) => {
+                        eprintln!("Warning: Invalid glob '{pattern}': {}", err.kind());
+                        continue;
                     }
+                };
+                
+                let found = files.len();
+                collect_dir_files(&glob_base(pattern), config, true, Some(&matcher), &mut files);
+                if files.len() == found {
+                    eprintln!("Warning: No files match '{pattern}'");
                 }
             }
             Err(_) => {
@@ -231,17 +1237,56 @@ fn get_files(patterns: &[&String]) -> Vec<String> {
         }
     }
     
+    // Overlapping arguments must not process a file twice
+    let mut seen = HashSet::new();
+    files.retain(|file| seen.insert(file.clone()));
     files
 }
 
-fn should_process_file(file_path: &str) -> bool {
-    let supported_extensions = [".js", ".jsx", ".ts", ".tsx", ".html", ".vue", ".astro"];
-    if let Some(extension) = Path::new(file_path).extension() {
-        if let Some(ext_str) = extension.to_str() {
-            return supported_extensions.contains(&format!(".{ext_str}").as_str());
+/// Collect the files below `root`, honouring `.gitignore` and `.ignore`
+/// files and the config's ignore list, and skipping hidden files.
+/// Symlinks are followed; loops are reported and skipped. With a `glob`,
+/// only the files it matches are collected.
+fn collect_dir_files(
+    root: &Path,
+    config: &Config,
+    recursive: bool,
+    glob: Option<&GlobMatcher>,
+    files: &mut Vec<String>,
+) {
+    let ignore = config.ignore_patterns().into_owned();
+    
+    let mut walker = WalkBuilder::new(root);
+    walker
+        .hidden(true)
+        .ignore(true)
+        .git_ignore(true)
+        .git_exclude(true)
+        .require_git(false)
+        .follow_links(true)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        // The directory argument itself is never ignored
+        .filter_entry(move |entry| entry.depth() == 0 || !ignore.is_match(entry.path()));
+    if !recursive {
+        walker.max_depth(Some(1));
+    }
+    
+    for entry in walker.build() {
+        match entry {
+            Ok(entry) => {
+                if !entry.file_type().is_some_and(|file_type| file_type.is_file()) {
+                    continue;
+                }
+                if glob.is_some_and(|glob| !glob.is_match(normalize_path(entry.path()))) {
+                    continue;
+                }
+                if let Some(path_str) = entry.path().to_str() {
+                    files.push(path_str.to_string());
+                }
+            }
+            Err(err) => eprintln!("Warning: {err}"),
         }
     }
-    false
 }
 
 #[cfg(test)]
@@ -250,17 +1295,118 @@ mod tests {
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+    fn read_options(max_file_size: u64) -> ReadOptions {
+        ReadOptions { max_file_size, encoding: encoding_rs::UTF_8 }
+    }
+
     #[test]
-    fn test_should_process_file() {
-        assert!(should_process_file("test.js"));
-        assert!(should_process_file("test.jsx"));
-        assert!(should_process_file("test.ts"));
-        assert!(should_process_file("test.tsx"));
-        assert!(should_process_file("test.html"));
-        assert!(should_process_file("test.vue"));
-        assert!(should_process_file("test.astro"));
-        assert!(!should_process_file("test.txt"));
-        assert!(!should_process_file("test.css"));
+    fn test_default_config_processes_supported_files() {
+        let config = Config::default();
+        assert!(config.should_process_file("test.js"));
+        assert!(config.should_process_file("test.jsx"));
+        assert!(config.should_process_file("test.ts"));
+        assert!(config.should_process_file("test.tsx"));
+        assert!(config.should_process_file("test.html"));
+        assert!(config.should_process_file("test.vue"));
+        assert!(config.should_process_file("test.astro"));
+        assert!(!config.should_process_file("test.txt"));
+        assert!(!config.should_process_file("test.css"));
+    }
+
+    #[test]
+    fn test_get_files_respects_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let nested = temp_dir.path().join("components");
+        let ignored = temp_dir.path().join("dist");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(&ignored).unwrap();
+        fs::write(temp_dir.path().join("index.html"), "").unwrap();
+        fs::write(nested.join("Button.tsx"), "").unwrap();
+        fs::write(ignored.join("bundle.js"), "").unwrap();
+        
+        let root = temp_dir.path().to_string_lossy().to_string();
+        let files = get_files(&[&root], &Config::default());
+        assert_eq!(files.len(), 2);
+        assert!(files.iter().any(|f| f.ends_with("Button.tsx")));
+        
+        let config = Config {
+            recursive: false,
+            ..Config::default()
+        };
+        let files = get_files(&[&root], &config);
+        assert_eq!(files.len(), 1);
+        assert!(files[0].ends_with("index.html"));
+    }
+
+    #[test]
+    fn test_get_files_honours_ignore_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/components/ui")).unwrap();
+        fs::create_dir_all(root.join("src/generated")).unwrap();
+        fs::create_dir_all(root.join(".storybook")).unwrap();
+        fs::write(root.join(".gitignore"), "generated/\n").unwrap();
+        fs::write(root.join("src/.ignore"), "*.stories.tsx\n").unwrap();
+        fs::write(root.join("src/components/ui/Button.tsx"), "").unwrap();
+        fs::write(root.join("src/components/ui/Button.stories.tsx"), "").unwrap();
+        fs::write(root.join("src/generated/icons.tsx"), "").unwrap();
+        fs::write(root.join(".storybook/preview.tsx"), "").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(root.join("src"), root.join("src/components/loop")).unwrap();
+        
+        let root = root.to_string_lossy().to_string();
+        let files = get_files(&[&root], &Config::default());
+        assert_eq!(files.len(), 1, "{files:?}");
+        assert!(files[0].ends_with("src/components/ui/Button.tsx"));
+    }
+
+    #[test]
+    fn test_get_files_expands_globs() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/components")).unwrap();
+        fs::write(root.join("src/App.vue"), "").unwrap();
+        fs::write(root.join("src/index.html"), "").unwrap();
+        fs::write(root.join("src/components/Button.tsx"), "").unwrap();
+        fs::write(root.join("src/components/Button.stories.tsx"), "").unwrap();
+        
+        let pattern = format!("{}/src/**/*.{{tsx,vue}}", root.display());
+        let config = Config {
+            ignore: vec!["*.stories.tsx".to_string()],
+            ..Config::default()
+        };
+        let files = get_files(&[&pattern, &pattern], &config);
+        assert_eq!(files.len(), 2, "{files:?}");
+        assert!(files[0].ends_with("src/App.vue"));
+        assert!(files[1].ends_with("src/components/Button.tsx"));
+        
+        assert_eq!(glob_base("src/**/*.tsx"), PathBuf::from("src"));
+        assert_eq!(glob_base("*.html"), PathBuf::from("."));
+        assert!(parse_glob("src/**/*.stories.tsx").is_ok());
+        assert!(parse_glob("src/[abc").is_err());
+    }
+
+    #[test]
+    fn test_process_file_too_large() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, r#"<div class="text-red-500 p-4 flex">test</div>"#).unwrap();
+        
+        let formatter = TailwindFormatter::new(false);
+        let result = process_file(
+            &formatter,
+            temp_file.path().to_str().unwrap(),
+            false,
+            None,
+            None,
+            read_options(10),
+            None,
+        );
+        
+        let err = result.unwrap_err();
+        assert!(matches!(err, SorterError::TooLarge { max_size: 10, .. }));
+        assert!(err.to_string().contains("too large"));
     }
 
     #[test]
@@ -273,11 +1419,13 @@ mod tests {
             &formatter,
             temp_file.path().to_str().unwrap(),
             false,
-            false,
+            None,
+            None,
+            read_options(MAX_FILE_SIZE),
             None,
         ).unwrap();
         
-        assert!(!result); // No changes needed
+        assert!(!result.result.changed); // No changes needed
     }
 
     #[test]
@@ -290,10 +1438,87 @@ mod tests {
             &formatter,
             temp_file.path().to_str().unwrap(),
             false,
-            false,
+            None,
+            None,
+            read_options(MAX_FILE_SIZE),
             None,
         ).unwrap();
         
-        assert!(result); // Changes needed
+        assert!(result.result.changed); // Changes needed
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_process_file_with_cache() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, r#"<div class="text-red-500 p-4 flex">test</div>"#).unwrap();
+        let path = temp_file.path().to_str().unwrap();
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let mut cache = Cache::load(cache_dir.path());
+        
+        let formatter = TailwindFormatter::new(false);
+        let config_hash = Cache::config_hash(&Config::default());
+        let result = process_file(&formatter, path, false, None, None, read_options(MAX_FILE_SIZE), Some((&cache, config_hash))).unwrap();
+        assert!(result.result.changed && !result.cached);
+        assert_eq!(result.formatted_key, None, "Files needing formatting are not cached");
+        
+        let result = process_file(&formatter, path, true, None, None, read_options(MAX_FILE_SIZE), Some((&cache, config_hash))).unwrap();
+        cache.insert(Path::new(path), result.formatted_key.expect("Written files are formatted"));
+        
+        let result = process_file(&formatter, path, false, None, None, read_options(MAX_FILE_SIZE), Some((&cache, config_hash))).unwrap();
+        assert!(result.cached && !result.result.changed);
+        let result = process_file(&formatter, path, false, None, None, read_options(MAX_FILE_SIZE), Some((&cache, config_hash + 1))).unwrap();
+        assert!(!result.cached, "Another config misses the cache");
+    }
+
+    #[test]
+    fn test_for_each_parallel_keeps_order() {
+        for threads in [1, 4] {
+            let mut seen = Vec::new();
+            for_each_parallel(
+                50,
+                threads,
+                |index| {
+                    // Later indices finish first
+                    thread::sleep(std::time::Duration::from_micros(((50 - index) * 20) as u64));
+                    index * 2
+                },
+                |index, result| seen.push((index, result)),
+            );
+            assert_eq!(seen, (0..50).map(|index| (index, index * 2)).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_parse_ranges() {
+        assert_eq!(parse_byte_range("120:480"), Ok(FormatRange::Bytes(120, 480)));
+        assert!(parse_byte_range("480:120").is_err());
+        assert!(parse_byte_range("120").is_err());
+        assert_eq!(parse_line_range("10-40"), Ok(FormatRange::Lines(10, 40)));
+        assert_eq!(parse_line_range("12"), Ok(FormatRange::Lines(12, 12)));
+        assert!(parse_line_range("0-4").is_err());
+        
+        assert_eq!(FormatRange::Bytes(2, 100).offsets("abcdef"), 2..6);
+        assert_eq!(FormatRange::Lines(2, 2).offsets("a\nb\nc"), 2..4);
+    }
+
+    #[test]
+    fn test_cursor_encoding_utf16() {
+        let source = "<p>é😀</p>\r\n<div class=\"p-4 text-red-500 flex\">x</div>";
+        // `😀` is two UTF-16 code units starting at unit 4 and byte 5
+        assert_eq!(CursorEncoding::Utf16.byte_offset(source, 4), 5);
+        assert_eq!(CursorEncoding::Utf16.byte_offset(source, 5), 5);
+        assert_eq!(CursorEncoding::Utf16.byte_offset(source, 6), 9);
+        assert_eq!(CursorEncoding::Utf16.byte_offset(source, 1000), source.len());
+        
+        let formatter = TailwindFormatter::new(true);
+        let x = source.find(">x<").unwrap() + 1;
+        let offset = source[..x].encode_utf16().count();
+        let cursor = CursorPosition { line: 0, column: 0, offset };
+        let result = format_source(&formatter, source, None, Some((cursor, CursorEncoding::Utf16)));
+        
+        assert_eq!(result.content, "<p>é😀</p>\r\n<div class=\"flex p-4 text-red-500\">x</div>");
+        let cursor = result.cursor_position.unwrap();
+        assert_eq!((cursor.line, cursor.column, cursor.offset), (1, 35, offset));
+    }
+
+}
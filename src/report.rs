@@ -0,0 +1,485 @@
+use crate::config::relative_to_cwd;
+use crate::encoding::SourceText;
+use crate::formatter::{ChangeKind, FormatResult};
+use serde::Serialize;
+use std::path::Path;
+
+/// Output formats for the results of a run
+pub const REPORTERS: [&str; 4] = ["text", "json", "sarif", "github"];
+
+/// A rewritten class attribute, with 1-based line and column
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangeRecord {
+    /// Byte offset of the attribute in the file, past a byte order mark.
+    /// Files in other encodings than UTF-8 count bytes of the decoded text.
+    pub start: usize,
+    /// Offset just past the attribute, counted like `start`
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    /// Column just past the attribute
+    pub end_column: usize,
+    /// `column` in UTF-16 code units, as SARIF and GitHub count columns
+    #[serde(skip)]
+    pub utf16_column: usize,
+    /// `end_column` in UTF-16 code units
+    #[serde(skip)]
+    pub utf16_end_column: usize,
+    pub kind: ChangeKind,
+    /// Class string before formatting
+    pub before: String,
+    /// Class string after formatting
+    pub after: String,
+}
+
+/// The result of formatting one file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileRecord {
+    pub path: String,
+    pub changed: bool,
+    pub error: Option<String>,
+    pub duplicates_removed: usize,
+    pub shorthands_collapsed: usize,
+    pub changes: Vec<ChangeRecord>,
+    /// Whether `start` and `end` of the changes are byte offsets into the
+    /// file, which they are not for files in other encodings than UTF-8
+    #[serde(skip)]
+    pub file_offsets: bool,
+}
+
+impl FileRecord {
+    /// The record of formatting `source`, the original content of `path`
+    pub fn from_result(path: &str, source: &str, result: &FormatResult) -> Self {
+        // Columns are byte offsets from the start of their line
+        let utf16_column = |offset: usize, column: usize| source[offset - column..offset].encode_utf16().count() + 1;
+        Self {
+            path: path.to_string(),
+            changed: result.changed,
+            error: None,
+            duplicates_removed: result.duplicates_removed,
+            shorthands_collapsed: result.shorthands_collapsed,
+            changes: result
+                .changes
+                .iter()
+                .map(|change| ChangeRecord {
+                    start: change.start,
+                    end: change.end,
+                    line: change.line + 1,
+                    column: change.column + 1,
+                    end_line: change.end_line + 1,
+                    end_column: change.end_column + 1,
+                    utf16_column: utf16_column(change.start, change.column),
+                    utf16_end_column: utf16_column(change.end, change.end_column),
+                    kind: change.kind,
+                    before: change.before.clone(),
+                    after: change.after.clone(),
+                })
+                .collect(),
+            file_offsets: true,
+        }
+    }
+
+    /// Make the offsets of the changes count bytes of the file `source` was
+    /// decoded from rather than of its text. Only UTF-8 files can be
+    /// mapped; the changes of files in other encodings keep offsets into the
+    /// decoded text.
+    pub fn in_file(mut self, source: &SourceText) -> Self {
+        self.file_offsets = source.file_offset(0).is_some();
+        for change in &mut self.changes {
+            change.start = source.file_offset(change.start).unwrap_or(change.start);
+            change.end = source.file_offset(change.end).unwrap_or(change.end);
+        }
+        self
+    }
+
+    pub fn failed(path: &str, error: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            changed: false,
+            error: Some(error.into()),
+            duplicates_removed: 0,
+            shorthands_collapsed: 0,
+            changes: Vec::new(),
+            file_offsets: true,
+        }
+    }
+}
+
+/// Totals over all files of a run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Summary {
+    pub files: usize,
+    /// Files formatted (with `--write`) or needing formatting
+    pub changed: usize,
+    pub unchanged: usize,
+    pub errors: usize,
+    /// Whether changes were written back to the files
+    pub write: bool,
+}
+
+impl Summary {
+    pub fn new(write: bool) -> Self {
+        Self { write, ..Self::default() }
+    }
+
+    pub fn add(&mut self, record: &FileRecord) {
+        self.files += 1;
+        if record.error.is_some() {
+            self.errors += 1;
+        } else if record.changed {
+            self.changed += 1;
+        } else {
+            self.unchanged += 1;
+        }
+    }
+}
+
+/// Presents the results of a run as files are processed
+pub trait Reporter {
+    fn file(&mut self, record: &FileRecord);
+
+    fn finish(&mut self, summary: &Summary);
+}
+
+/// Build the reporter named on the command line
+pub fn reporter(name: &str, write: bool, verbose: bool) -> Box<dyn Reporter> {
+    match name {
+        "json" => Box::new(JsonReporter::default()),
+        "sarif" => Box::new(SarifReporter::default()),
+        "github" => Box::new(GithubReporter { write }),
+        _ => Box::new(TextReporter { write, verbose }),
+    }
+}
+
+/// Human readable output
+pub struct TextReporter {
+    pub write: bool,
+    pub verbose: bool,
+}
+
+impl Reporter for TextReporter {
+    fn file(&mut self, record: &FileRecord) {
+        let path = &record.path;
+        if let Some(error) = &record.error {
+            eprintln!("✗ Error processing {path}: {error}");
+            return;
+        }
+
+        if record.duplicates_removed > 0 {
+            println!(
+                "{} {} duplicate class{} in {path}",
+                if self.write { "Removed" } else { "Found" },
+                record.duplicates_removed,
+                if record.duplicates_removed == 1 { "" } else { "es" }
+            );
+        }
+
+        if record.shorthands_collapsed > 0 {
+            println!(
+                "{} {} shorthand{} in {path}",
+                if self.write { "Collapsed" } else { "Found" },
+                record.shorthands_collapsed,
+                if record.shorthands_collapsed == 1 { "" } else { "s" }
+            );
+        }
+
+        if !self.verbose {
+            return;
+        }
+        if record.changed {
+            let note = if record.changes.iter().all(|c| c.kind == ChangeKind::Whitespace) {
+                " (whitespace only)"
+            } else {
+                ""
+            };
+            if self.write {
+                println!("✓ Formatted {path}{note}");
+            } else {
+                println!("⚠ {path} needs formatting{note}");
+            }
+        } else {
+            println!("✓ {path} is already formatted");
+        }
+    }
+
+    fn finish(&mut self, summary: &Summary) {
+        if self.verbose || !self.write {
+            print_summary(summary);
+        }
+    }
+}
+
+fn print_summary(summary: &Summary) {
+    println!("\nProcessed {} files:", summary.files);
+    println!(
+        "  {} files {}",
+        summary.changed,
+        if summary.write { "formatted" } else { "need formatting" }
+    );
+    println!("  {} files already formatted", summary.unchanged);
+    if summary.errors > 0 {
+        println!("  {} files had errors", summary.errors);
+    }
+}
+
+/// A kind of problem reported for a changed attribute
+struct Rule {
+    id: &'static str,
+    /// Title of GitHub annotations
+    title: &'static str,
+    description: &'static str,
+}
+
+const UNSORTED: Rule = Rule {
+    id: "unsorted-classes",
+    title: "Unsorted Tailwind CSS classes",
+    description: "Tailwind CSS classes are not sorted",
+};
+const DUPLICATES: Rule = Rule {
+    id: "duplicate-classes",
+    title: "Duplicate Tailwind CSS classes",
+    description: "Tailwind CSS classes are duplicated",
+};
+const SHORTHANDS: Rule = Rule {
+    id: "class-shorthands",
+    title: "Collapsible Tailwind CSS classes",
+    description: "Tailwind CSS classes can be collapsed into shorthands",
+};
+const WHITESPACE: Rule = Rule {
+    id: "class-whitespace",
+    title: "Unnormalized class whitespace",
+    description: "Whitespace in class attribute is not normalized",
+};
+
+/// Every rule, in the order of the SARIF log
+const RULES: [&Rule; 4] = [&UNSORTED, &DUPLICATES, &SHORTHANDS, &WHITESPACE];
+
+/// Rule and message describing a change. Sorted changes that only drop
+/// classes are duplicates, and those adding classes are shorthands.
+fn change_message(change: &ChangeRecord) -> (&'static Rule, String) {
+    let expected = change.after.split_whitespace().collect::<Vec<_>>().join(" ");
+    let before: Vec<&str> = change.before.split_whitespace().collect();
+    let mut remaining = before.iter();
+    let rule = match change.kind {
+        ChangeKind::Whitespace => &WHITESPACE,
+        _ if change.after.split_whitespace().any(|class| !before.contains(&class)) => &SHORTHANDS,
+        _ if change.after.split_whitespace().all(|class| remaining.any(|&kept| kept == class)) => &DUPLICATES,
+        ChangeKind::Sorted => &UNSORTED,
+    };
+    (rule, format!("{}, expected `{expected}`", rule.description))
+}
+
+/// A path as a relative URI with forward slashes, as code scanning expects
+fn artifact_uri(path: &str) -> String {
+    relative_to_cwd(Path::new(path))
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// SARIF 2.1.0 log with one result per changed attribute, for code scanning
+#[derive(Default)]
+pub struct SarifReporter {
+    results: Vec<serde_json::Value>,
+    notifications: Vec<serde_json::Value>,
+}
+
+impl Reporter for SarifReporter {
+    fn file(&mut self, record: &FileRecord) {
+        let uri = artifact_uri(&record.path);
+        if let Some(error) = &record.error {
+            self.notifications.push(serde_json::json!({
+                "level": "error",
+                "message": { "text": error },
+                "locations": [{ "physicalLocation": { "artifactLocation": { "uri": uri } } }],
+            }));
+            return;
+        }
+
+        for change in &record.changes {
+            let (rule, message) = change_message(change);
+            let mut region = serde_json::json!({
+                "startLine": change.line,
+                "startColumn": change.utf16_column,
+                "endLine": change.end_line,
+                "endColumn": change.utf16_end_column,
+            });
+            // SARIF byte offsets count bytes of the file
+            if record.file_offsets {
+                region["byteOffset"] = change.start.into();
+                region["byteLength"] = (change.end - change.start).into();
+            }
+            self.results.push(serde_json::json!({
+                "ruleId": rule.id,
+                "level": "warning",
+                "message": { "text": message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri },
+                        "region": region,
+                    },
+                }],
+            }));
+        }
+    }
+
+    fn finish(&mut self, summary: &Summary) {
+        let log = serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "biome-tailwind-sorter",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": "https://github.com/rizkyviali/biome-tailwind-sorter",
+                        "rules": RULES.map(|rule| serde_json::json!({
+                            "id": rule.id,
+                            "shortDescription": { "text": rule.description },
+                        })),
+                    },
+                },
+                "columnKind": "utf16CodeUnits",
+                "invocations": [{
+                    "executionSuccessful": summary.errors == 0,
+                    "toolExecutionNotifications": self.notifications,
+                }],
+                "results": self.results,
+            }],
+        });
+        println!("{}", serde_json::to_string_pretty(&log).unwrap_or_default());
+    }
+}
+
+/// GitHub Actions workflow commands, which show up as annotations on pull
+/// requests
+pub struct GithubReporter {
+    pub write: bool,
+}
+
+/// Escape data of a workflow command
+fn escape_data(value: &str) -> String {
+    value.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escape a property value of a workflow command
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+impl Reporter for GithubReporter {
+    fn file(&mut self, record: &FileRecord) {
+        let file = escape_property(&artifact_uri(&record.path));
+        if let Some(error) = &record.error {
+            println!("::error file={file}::{}", escape_data(error));
+            return;
+        }
+
+        // Files rewritten with --write need no action
+        if self.write {
+            return;
+        }
+        for change in &record.changes {
+            let (rule, message) = change_message(change);
+            println!(
+                "::warning file={file},line={},col={},endLine={},endColumn={},title={}::{}",
+                change.line,
+                change.utf16_column,
+                change.end_line,
+                change.utf16_end_column,
+                escape_property(rule.title),
+                escape_data(&message)
+            );
+        }
+    }
+
+    fn finish(&mut self, summary: &Summary) {
+        print_summary(summary);
+    }
+}
+
+/// A single JSON document with a record per file and the summary, printed
+/// when the run finishes
+#[derive(Default)]
+pub struct JsonReporter {
+    files: Vec<FileRecord>,
+}
+
+impl Reporter for JsonReporter {
+    fn file(&mut self, record: &FileRecord) {
+        self.files.push(record.clone());
+    }
+
+    fn finish(&mut self, summary: &Summary) {
+        let report = serde_json::json!({
+            "files": self.files,
+            "summary": summary,
+        });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter::TailwindFormatter;
+
+    #[test]
+    fn test_file_record_from_result() {
+        let source = "<main>\n  <div class=\"p-4 flex\">x</div>\n</main>";
+        let result = TailwindFormatter::new(false).format_document(source, None);
+        let record = FileRecord::from_result("index.html", source, &result);
+
+        assert!(record.changed);
+        assert_eq!(record.changes.len(), 1);
+        let change = &record.changes[0];
+        assert_eq!((change.line, change.column), (2, 8));
+        assert_eq!(&source[change.start..change.end], "class=\"p-4 flex\"");
+        assert_eq!(change.before, "p-4 flex");
+        assert_eq!(change.after, "flex p-4");
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["changes"][0]["kind"], "sorted");
+        assert_eq!(json["error"], serde_json::Value::Null);
+
+        // SARIF and GitHub count columns in UTF-16 code units
+        let source = "<p>😀 é</p><b class=\"p-4 flex\">";
+        let result = TailwindFormatter::new(false).format_document(source, None);
+        let change = &FileRecord::from_result("a.html", source, &result).changes[0];
+        assert_eq!((change.column, change.utf16_column), (18, 15));
+        assert_eq!((change.end_column, change.utf16_end_column), (34, 31));
+    }
+
+    #[test]
+    fn test_change_messages() {
+        let source = "<a class=\"p-4\n  flex\">";
+        let result = TailwindFormatter::new(false).format_document(source, None);
+        let record = FileRecord::from_result("a.html", source, &result);
+        let change = &record.changes[0];
+        assert_eq!((change.line, change.end_line, change.end_column), (1, 2, 8));
+
+        let (rule, message) = change_message(change);
+        assert_eq!(rule.id, "unsorted-classes");
+        assert_eq!(message, "Tailwind CSS classes are not sorted, expected `flex p-4`");
+
+        let mut change = change.clone();
+        change.before = "flex p-4 flex".to_string();
+        assert_eq!(change_message(&change).0.title, "Duplicate Tailwind CSS classes");
+        change.before = "mt-2 mb-2 flex".to_string();
+        change.after = "flex my-2".to_string();
+        assert_eq!(change_message(&change).0.id, "class-shorthands");
+        change.kind = ChangeKind::Whitespace;
+        assert_eq!(change_message(&change).0.title, "Unnormalized class whitespace");
+        assert_eq!(escape_property("a,b:c%\n"), "a%2Cb%3Ac%25%0A");
+    }
+
+    #[test]
+    fn test_summary_counts() {
+        let mut summary = Summary::new(false);
+        let result = TailwindFormatter::new(false).format_document("<a class=\"flex\">", None);
+        summary.add(&FileRecord::from_result("a.html", "<a class=\"flex\">", &result));
+        summary.add(&FileRecord::failed("b.html", "too large"));
+
+        assert_eq!((summary.files, summary.changed, summary.unchanged, summary.errors), (2, 0, 1, 1));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::class_extractor::is_tailwind_class;
+use crate::config::Config;
 use phf::phf_map;
 use std::cmp::Ordering;
 
@@ -8,6 +10,30 @@ pub struct TailwindClass {
     pub modifier: Option<String>,
 }
 
+impl TailwindClass {
+    /// The class without its variants, e.g. `!p-4` for `md:hover:!p-4`
+    pub fn utility(&self) -> &str {
+        self.name.rsplit(':').next().unwrap_or(&self.name)
+    }
+
+    /// Variants in a canonical order, so `hover:md:p-4` and `md:hover:p-4`
+    /// compare equal
+    pub fn variant_set(&self) -> Vec<&str> {
+        let mut variants: Vec<&str> = self
+            .modifier
+            .as_deref()
+            .map(|m| m.split(':').collect())
+            .unwrap_or_default();
+        variants.sort_unstable();
+        variants
+    }
+
+    /// Whether the class uses the `!` important modifier
+    pub fn is_important(&self) -> bool {
+        self.utility().starts_with('!')
+    }
+}
+
 static TAILWIND_ORDER_MAP: phf::Map<&'static str, u32> = phf_map! {
     // Layout
     "container" => 0,
@@ -346,40 +372,143 @@ fn get_responsive_order(modifier: &str) -> Option<usize> {
     responsive_order.iter().position(|&x| x == modifier)
 }
 
-pub fn sort_tailwind_classes(classes: &[String]) -> Vec<String> {
-    let mut parsed_classes: Vec<TailwindClass> = classes
-        .iter()
-        .map(|c| parse_tailwind_class(c))
-        .collect();
-    
-    parsed_classes.sort_by(|a, b| {
-        // First sort by order
-        match a.order.cmp(&b.order) {
-            Ordering::Equal => {
-                // Then by modifier (responsive, pseudo-classes, etc.)
-                let a_modifier = a.modifier.as_deref().unwrap_or("");
-                let b_modifier = b.modifier.as_deref().unwrap_or("");
-                
-                match (a_modifier.is_empty(), b_modifier.is_empty()) {
-                    (true, false) => Ordering::Less,   // Base classes first
-                    (false, true) => Ordering::Greater,
-                    (true, true) | (false, false) => {
-                        // Sort responsive modifiers in order: sm, md, lg, xl, 2xl
-                        match (get_responsive_order(a_modifier), get_responsive_order(b_modifier)) {
-                            (Some(a_resp), Some(b_resp)) => a_resp.cmp(&b_resp),
-                            _ => a_modifier.cmp(b_modifier),
-                        }
-                        .then_with(|| a.name.cmp(&b.name))
+/// Named groups usable in `Config.custom_order`, mapped to the orders they cover
+static GROUP_ORDERS: &[(&str, &[u32])] = &[
+    ("layout", &[0, 10, 20]),
+    ("position", &[30]),
+    ("inset", &[40]),
+    ("visibility", &[50]),
+    ("z-index", &[60]),
+    ("flexbox-grid", &[70]),
+    ("spacing", &[80]),
+    ("sizing", &[90]),
+    ("typography", &[100]),
+    ("backgrounds", &[110]),
+    ("borders", &[120]),
+    ("effects", &[130]),
+    ("filters", &[140]),
+    ("tables", &[150]),
+    ("transitions", &[160]),
+    ("transforms", &[170]),
+    ("interactivity", &[180]),
+    ("svg", &[190]),
+    ("accessibility", &[200]),
+    ("official", &[210]),
+];
+
+fn compare_classes(a: &TailwindClass, b: &TailwindClass) -> Ordering {
+    // First sort by order
+    match a.order.cmp(&b.order) {
+        Ordering::Equal => {
+            // Then by modifier (responsive, pseudo-classes, etc.)
+            let a_modifier = a.modifier.as_deref().unwrap_or("");
+            let b_modifier = b.modifier.as_deref().unwrap_or("");
+            
+            match (a_modifier.is_empty(), b_modifier.is_empty()) {
+                (true, false) => Ordering::Less,   // Base classes first
+                (false, true) => Ordering::Greater,
+                (true, true) | (false, false) => {
+                    // Sort responsive modifiers in order: sm, md, lg, xl, 2xl
+                    match (get_responsive_order(a_modifier), get_responsive_order(b_modifier)) {
+                        (Some(a_resp), Some(b_resp)) => a_resp.cmp(&b_resp),
+                        _ => a_modifier.cmp(b_modifier),
                     }
+                    .then_with(|| a.name.cmp(&b.name))
                 }
             }
-            other => other,
         }
-    });
-    
-    parsed_classes.into_iter().map(|c| c.name).collect()
+        other => other,
+    }
+}
+
+#[allow(dead_code)]
+pub fn sort_tailwind_classes(classes: &[String]) -> Vec<String> {
+    Sorter::default().sort(classes)
 }
 
+/// Class sorter honouring the ordering options of a [`Config`]
+#[derive(Debug, Clone, Default)]
+pub struct Sorter {
+    custom_order: Vec<String>,
+    custom_classes: Vec<String>,
+    sort_custom_classes: bool,
+}
+
+impl Sorter {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            custom_order: config.custom_order.clone().unwrap_or_default(),
+            custom_classes: config.custom_classes.clone(),
+            sort_custom_classes: config.sort_custom_classes,
+        }
+    }
+
+    /// Check if a class is a custom class declared in the config
+    pub fn is_custom_class(&self, class_name: &str) -> bool {
+        let utility = class_name.rsplit(':').next().unwrap_or(class_name);
+        self.custom_classes
+            .iter()
+            .any(|custom| custom == class_name || custom == utility)
+    }
+
+    /// Check if a class is a Tailwind class or one of the configured custom classes
+    pub fn is_tailwind_class(&self, class_name: &str) -> bool {
+        self.is_custom_class(class_name) || is_tailwind_class(class_name)
+    }
+
+    pub fn contains_tailwind_classes(&self, class_names: &[String]) -> bool {
+        class_names.iter().any(|class_name| self.is_tailwind_class(class_name))
+    }
+
+    /// Position of the first `custom_order` entry matching the class, or
+    /// `custom_order.len()` when none does
+    fn custom_rank(&self, class: &TailwindClass) -> usize {
+        let utility = class.name.rsplit(':').next().unwrap_or(&class.name);
+        
+        self.custom_order
+            .iter()
+            .position(|entry| {
+                if let Some((_, orders)) = GROUP_ORDERS.iter().find(|(name, _)| name == entry) {
+                    return orders.contains(&class.order);
+                }
+                
+                // Anything else is treated as a class prefix
+                utility == entry
+                    || (entry.ends_with('-') && utility.starts_with(entry.as_str()))
+                    || utility.starts_with(&format!("{entry}-"))
+            })
+            .unwrap_or(self.custom_order.len())
+    }
+
+    pub fn sort(&self, classes: &[String]) -> Vec<String> {
+        let mut parsed_classes: Vec<TailwindClass> = Vec::with_capacity(classes.len());
+        let mut unsorted_custom: Vec<String> = Vec::new();
+        
+        for class in classes {
+            if !self.sort_custom_classes && self.is_custom_class(class) {
+                // Unsorted custom classes keep their relative order at the end
+                unsorted_custom.push(class.clone());
+            } else {
+                parsed_classes.push(parse_tailwind_class(class));
+            }
+        }
+        
+        let mut ranked: Vec<(usize, TailwindClass)> = parsed_classes
+            .into_iter()
+            .map(|c| (self.custom_rank(&c), c))
+            .collect();
+        
+        ranked.sort_by(|(a_rank, a), (b_rank, b)| {
+            a_rank.cmp(b_rank).then_with(|| compare_classes(a, b))
+        });
+        
+        ranked
+            .into_iter()
+            .map(|(_, c)| c.name)
+            .chain(unsorted_custom)
+            .collect()
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -414,4 +543,58 @@ mod tests {
         let sorted = sort_tailwind_classes(&classes);
         assert_eq!(sorted, vec!["text-red-500", "md:text-red-500", "lg:text-red-500"]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_sorter_custom_order_groups() {
+        let config = Config {
+            custom_order: Some(vec!["typography".to_string(), "layout".to_string()]),
+            ..Config::default()
+        };
+        let sorter = Sorter::from_config(&config);
+        let classes = vec![
+            "p-4".to_string(),
+            "flex".to_string(),
+            "text-red-500".to_string(),
+        ];
+        assert_eq!(sorter.sort(&classes), vec!["text-red-500", "flex", "p-4"]);
+    }
+
+    #[test]
+    fn test_sorter_custom_order_prefixes() {
+        let config = Config {
+            custom_order: Some(vec!["bg".to_string(), "p-".to_string()]),
+            ..Config::default()
+        };
+        let sorter = Sorter::from_config(&config);
+        let classes = vec![
+            "flex".to_string(),
+            "p-4".to_string(),
+            "bg-blue-500".to_string(),
+        ];
+        assert_eq!(sorter.sort(&classes), vec!["bg-blue-500", "p-4", "flex"]);
+    }
+
+    #[test]
+    fn test_sorter_custom_classes() {
+        let mut config = Config {
+            custom_classes: vec!["btn".to_string(), "card".to_string()],
+            ..Config::default()
+        };
+        let classes = vec![
+            "card".to_string(),
+            "p-4".to_string(),
+            "btn".to_string(),
+            "flex".to_string(),
+        ];
+
+        let sorter = Sorter::from_config(&config);
+        assert!(sorter.is_tailwind_class("hover:btn"));
+        assert!(sorter.contains_tailwind_classes(&["card".to_string()]));
+        assert_eq!(sorter.sort(&classes), vec!["flex", "p-4", "card", "btn"]);
+
+        config.sort_custom_classes = true;
+        config.custom_order = Some(vec!["btn".to_string()]);
+        let sorter = Sorter::from_config(&config);
+        assert_eq!(sorter.sort(&classes), vec!["btn", "flex", "p-4", "card"]);
+    }
+}
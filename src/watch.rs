@@ -0,0 +1,130 @@
+use notify::event::{AccessKind, AccessMode};
+use notify::{Event, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// How long to wait for more changes after one, so a burst of saves is
+/// handled at once
+pub const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Interval of the polling fallback
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Watches paths for changed files, with OS notifications when available and
+/// polling otherwise
+pub struct FileWatcher {
+    /// Watched files and directories, as absolute paths
+    roots: Vec<PathBuf>,
+    events: Receiver<notify::Result<Event>>,
+    /// Kept alive while watching
+    _watcher: Box<dyn Watcher>,
+    pub polling: bool,
+}
+
+impl FileWatcher {
+    /// Watch `paths`, directories recursively
+    pub fn new(paths: &[PathBuf]) -> Result<Self, String> {
+        let roots = paths
+            .iter()
+            .map(|path| {
+                path.canonicalize()
+                    .map_err(|err| format!("Cannot watch '{}': {err}", path.display()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let (sender, events) = mpsc::channel();
+        let native = RecommendedWatcher::new(sender.clone(), notify::Config::default())
+            .map_err(|err| err.to_string())
+            .and_then(|watcher| watch_all(watcher, &roots));
+        if let Ok(watcher) = native {
+            return Ok(Self { roots, events, _watcher: Box::new(watcher), polling: false });
+        }
+
+        // Notifications may be unavailable (e.g. network drives or an
+        // exhausted inotify watch limit)
+        let config = notify::Config::default().with_poll_interval(POLL_INTERVAL);
+        let watcher = PollWatcher::new(sender, config).map_err(|err| err.to_string())?;
+        let watcher = watch_all(watcher, &roots)?;
+        Ok(Self { roots, events, _watcher: Box::new(watcher), polling: true })
+    }
+
+    /// Wait for files to be created or modified, then until no more change
+    /// for `debounce`, and return the changed files in sorted order. Returns
+    /// `None` once the watcher stops.
+    pub fn next_batch(&self, debounce: Duration) -> Option<Vec<PathBuf>> {
+        let mut changed = BTreeSet::new();
+        loop {
+            let event = if changed.is_empty() {
+                self.events.recv().map_err(|_| RecvTimeoutError::Disconnected)
+            } else {
+                self.events.recv_timeout(debounce)
+            };
+            match event {
+                Ok(Ok(event)) if is_write(&event.kind) => changed.extend(
+                    event
+                        .paths
+                        .into_iter()
+                        .filter(|path| self.roots.iter().any(|root| path.starts_with(root))),
+                ),
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => return Some(changed.into_iter().collect()),
+                Err(RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    }
+}
+
+/// Watch directories recursively, and files through their directory, which
+/// keeps working when editors save by replacing the file
+fn watch_all<W: Watcher>(mut watcher: W, roots: &[PathBuf]) -> Result<W, String> {
+    for root in roots {
+        let (path, mode) = match root.parent() {
+            Some(parent) if !root.is_dir() => (parent, RecursiveMode::NonRecursive),
+            _ => (root.as_path(), RecursiveMode::Recursive),
+        };
+        watcher
+            .watch(path, mode)
+            .map_err(|err| format!("Cannot watch '{}': {err}", root.display()))?;
+    }
+    Ok(watcher)
+}
+
+/// Whether an event may have changed the content of its files
+fn is_write(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Access(AccessKind::Close(AccessMode::Write))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_next_batch_collects_a_burst_of_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let watcher = FileWatcher::new(std::slice::from_ref(&root)).unwrap();
+
+        fs::write(root.join("b.html"), "b").unwrap();
+        fs::write(root.join("a.html"), "a").unwrap();
+        fs::write(root.join("a.html"), "aa").unwrap();
+
+        let batch = watcher.next_batch(Duration::from_millis(if watcher.polling { 1500 } else { 300 })).unwrap();
+        assert_eq!(batch, vec![root.join("a.html"), root.join("b.html")]);
+
+        // A watched file is followed through its directory, ignoring siblings
+        let file = root.join("a.html");
+        let watcher = FileWatcher::new(std::slice::from_ref(&file)).unwrap();
+        fs::write(root.join("b.html"), "bb").unwrap();
+        fs::write(root.join("a.html.tmp"), "aaa").unwrap();
+        fs::rename(root.join("a.html.tmp"), &file).unwrap();
+
+        let batch = watcher.next_batch(Duration::from_millis(if watcher.polling { 1500 } else { 300 })).unwrap();
+        assert_eq!(batch, vec![file]);
+    }
+}
@@ -243,4 +243,935 @@ fn test_cli_multiline_classes() {
     let bg_pos = formatted_content.find("bg-red-500").unwrap();
     assert!(p4_pos < font_pos && font_pos < bg_pos, 
             "Classes should be in correct order. Content: {formatted_content}");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_cli_remove_duplicates() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let file_path = create_test_file(
+        r#"<div class="p-4 flex p-4">test</div>"#,
+        &temp_dir
+    );
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .args(["--write", "--remove-duplicates", &file_path])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Removed 1 duplicate class"), 
+            "Should report removed duplicates. Got: {stdout}");
+    
+    let formatted_content = fs::read_to_string(&file_path).expect("Failed to read formatted file");
+    assert!(formatted_content.contains(r#"class="flex p-4""#), 
+            "Duplicates should be removed. Got: {formatted_content}");
+}
+
+#[test]
+fn test_cli_lint_reports_conflicts() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let original = r#"<div class="p-4 flex p-2">test</div>"#;
+    let file_path = create_test_file(original, &temp_dir);
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .args(["--lint", &file_path])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 1, "Should exit with code 1 when conflicts found");
+    
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("{file_path}:1:22:")), 
+            "Should report the conflict location. Got: {stdout}");
+    
+    let content = fs::read_to_string(&file_path).expect("Failed to read file");
+    assert_eq!(content, original, "Lint mode should not rewrite files");
+}
+
+#[test]
+fn test_cli_fix_shorthands() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let file_path = create_test_file(
+        r#"<div class="px-4 flex py-4">test</div>"#,
+        &temp_dir
+    );
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .args(["--write", "--fix-shorthands", &file_path])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    
+    let formatted_content = fs::read_to_string(&file_path).expect("Failed to read formatted file");
+    assert!(formatted_content.contains(r#"class="flex p-4""#), 
+            "Pairs should be collapsed into shorthands. Got: {formatted_content}");
+}
+
+#[test]
+fn test_cli_uses_config_file() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let file_path = create_test_file(
+        r#"<div class="card p-4 flex p-4">test</div>"#,
+        &temp_dir
+    );
+    let config_path = temp_dir.path().join("sorter.json");
+    fs::write(&config_path, r#"{ "custom_classes": ["card"], "remove_duplicates": true }"#)
+        .expect("Failed to write config file");
+    let config_path = config_path.to_string_lossy().to_string();
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .args(["--write", "--verbose", "--config", &config_path, &file_path])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("Using config {config_path}")), 
+            "Should print the config file used. Got: {stdout}");
+    
+    let formatted_content = fs::read_to_string(&file_path).expect("Failed to read formatted file");
+    assert!(formatted_content.contains(r#"class="flex p-4 card""#), 
+            "Config options should be applied. Got: {formatted_content}");
+}
+
+#[test]
+fn test_cli_invalid_config_reports_location() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let file_path = create_test_file(
+        r#"<div class="text-red-500 p-4 flex">test</div>"#,
+        &temp_dir
+    );
+    fs::write(
+        temp_dir.path().join("tailwindsorter.toml"),
+        "recursive = true\nprint_width = \"wide\"\n",
+    ).expect("Failed to write config file");
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .current_dir(temp_dir.path())
+        .args(["--check", &file_path])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 3, "Should exit with code 3 on invalid config");
+    
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("tailwindsorter.toml:2:15:"), 
+            "Should report the error location. Got: {stderr}");
+}
+
+#[test]
+fn test_cli_reads_biome_use_sorted_classes_options() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let file_path = temp_dir.path().join("Button.tsx");
+    fs::write(
+        &file_path,
+        r#"const button = <div className={clsx("text-red-500 p-4 flex")} />;"#,
+    ).expect("Failed to write test file");
+    fs::write(
+        temp_dir.path().join("biome.json"),
+        r#"{
+  "linter": {
+    "rules": {
+      "nursery": {
+        "useSortedClasses": {
+          "level": "warn",
+          "options": { "functions": ["clsx"] }
+        }
+      }
+    }
+  }
+}"#,
+    ).expect("Failed to write biome.json");
+    let file_path = file_path.to_string_lossy().to_string();
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .current_dir(temp_dir.path())
+        .args(["--write", &file_path])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    
+    let formatted_content = fs::read_to_string(&file_path).expect("Failed to read formatted file");
+    assert!(formatted_content.contains(r#"clsx("flex p-4 text-red-500")"#), 
+            "Classes passed to clsx should be sorted. Got: {formatted_content}");
+}
+
+#[test]
+fn test_cli_uses_nearest_config_and_overrides() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("apps/web")).expect("Failed to create dirs");
+    fs::create_dir_all(root.join("packages/legacy")).expect("Failed to create dirs");
+    fs::write(
+        root.join("tailwindsorter.config.json"),
+        r#"{ "overrides": [{ "files": ["packages/legacy/**"], "remove_duplicates": true }] }"#,
+    ).expect("Failed to write config file");
+    fs::write(
+        root.join("apps/web/tailwindsorter.config.json"),
+        r#"{ "custom_classes": ["btn"] }"#,
+    ).expect("Failed to write config file");
+    let content = r#"<div class="btn p-4 flex p-4">test</div>"#;
+    fs::write(root.join("apps/web/index.html"), content).expect("Failed to write test file");
+    fs::write(root.join("packages/legacy/index.html"), content).expect("Failed to write test file");
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .current_dir(root)
+        .args(["--write", "."])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    
+    let web = fs::read_to_string(root.join("apps/web/index.html")).expect("Failed to read file");
+    assert!(web.contains(r#"class="flex p-4 p-4 btn""#), 
+            "The nearest config should apply. Got: {web}");
+    let legacy = fs::read_to_string(root.join("packages/legacy/index.html")).expect("Failed to read file");
+    assert!(legacy.contains(r#"class="flex p-4 btn""#), 
+            "The override should apply. Got: {legacy}");
+}
+
+#[test]
+fn test_cli_nested_config_ignore_and_extensions() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("apps/web/legacy")).expect("Failed to create dirs");
+    fs::create_dir_all(root.join("apps/docs")).expect("Failed to create dirs");
+    fs::write(
+        root.join("apps/web/tailwindsorter.config.json"),
+        r#"{ "ignore": ["legacy"] }"#,
+    ).expect("Failed to write config file");
+    fs::write(
+        root.join("apps/docs/tailwindsorter.config.json"),
+        r#"{ "extensions": ["mdx"] }"#,
+    ).expect("Failed to write config file");
+    let content = r#"<div class="text-red-500 p-4 flex">test</div>"#;
+    fs::write(root.join("apps/web/legacy/a.html"), content).expect("Failed to write test file");
+    fs::write(root.join("apps/docs/intro.mdx"), content).expect("Failed to write test file");
+    // Path patterns are relative to their config's directory
+    fs::create_dir_all(root.join("apps/site/src")).expect("Failed to create dirs");
+    fs::create_dir_all(root.join("apps/site/src/legacy")).expect("Failed to create dirs");
+    fs::write(
+        root.join("apps/site/tailwindsorter.config.json"),
+        r#"{ "include": ["src/**"], "ignore": ["src/legacy/**"] }"#,
+    ).expect("Failed to write config file");
+    fs::write(root.join("apps/site/src/page.html"), content).expect("Failed to write test file");
+    fs::write(root.join("apps/site/src/legacy/old.html"), content).expect("Failed to write test file");
+    fs::write(root.join("apps/site/other.html"), content).expect("Failed to write test file");
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .current_dir(root)
+        .args(["--check", "--verbose", "."])
+        .output()
+        .expect("Failed to execute command");
+        
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("a.html"),
+            "The nested config's ignore list should apply. Got: {stdout}");
+    assert!(stdout.contains("intro.mdx"),
+            "The nested config's extensions should apply. Got: {stdout}");
+    assert!(stdout.contains("apps/site/src/page.html"),
+            "The nested config's include should match below its directory. Got: {stdout}");
+    assert!(!stdout.contains("old.html") && !stdout.contains("other.html"),
+            "The nested config's path patterns should apply. Got: {stdout}");
+    assert_eq!(output.status.code().unwrap(), 1, "Should exit with code 1");
+}
+
+#[test]
+fn test_cli_unknown_config_key_suggests_name() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let file_path = create_test_file(
+        r#"<div class="text-red-500 p-4 flex">test</div>"#,
+        &temp_dir
+    );
+    fs::write(
+        temp_dir.path().join("tailwindsorter.config.json"),
+        r#"{ "extentions": ["html"] }"#,
+    ).expect("Failed to write config file");
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .current_dir(temp_dir.path())
+        .args(["--check", &file_path])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 3, "Should exit with code 3 on invalid config");
+    
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("did you mean `extensions`?"), 
+            "Should suggest the closest key. Got: {stderr}");
+}
+
+#[test]
+fn test_cli_print_schema() {
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .arg("print-schema")
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .expect("Should print valid JSON");
+    assert_eq!(schema["additionalProperties"], false);
+    assert!(schema["properties"]["extensions"].is_object());
+}
+
+#[test]
+fn test_cli_init_writes_starter_config() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(
+        temp_dir.path().join("package.json"),
+        r#"{ "devDependencies": { "tailwindcss": "^3.4.0", "clsx": "^2.0.0" } }"#,
+    ).expect("Failed to write package.json");
+    create_test_file(r#"<div class="p-4">test</div>"#, &temp_dir);
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .current_dir(temp_dir.path())
+        .arg("init")
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Tailwind CSS ^3.4.0"), "Should print the Tailwind version. Got: {stdout}");
+    assert!(stdout.contains("Created tailwindsorter.config.json"), "Should print the file written. Got: {stdout}");
+    
+    let config: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(temp_dir.path().join("tailwindsorter.config.json")).expect("Failed to read config"),
+    ).expect("Should write valid JSON");
+    assert_eq!(config["extensions"], serde_json::json!(["html"]));
+    assert_eq!(config["functions"], serde_json::json!(["clsx"]));
+    
+    // An existing config is not overwritten without --force
+    let output = Command::new(&binary)
+        .current_dir(temp_dir.path())
+        .arg("init")
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code().unwrap(), 2, "Should refuse to overwrite the config");
+    
+    // --force with another format replaces the config
+    let output = Command::new(&binary)
+        .current_dir(temp_dir.path())
+        .args(["init", "--force", "--format", "toml"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    let toml = fs::read_to_string(temp_dir.path().join("tailwindsorter.toml")).expect("Should write TOML");
+    assert!(toml.contains("functions = [\"clsx\"]"), "Got: {toml}");
+    assert!(!temp_dir.path().join("tailwindsorter.config.json").exists(), "Should remove the JSON config");
+}
+
+#[test]
+fn test_cli_walks_directories_honouring_gitignore() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src/components/ui")).expect("Failed to create dirs");
+    fs::create_dir_all(root.join("src/vendor")).expect("Failed to create dirs");
+    fs::write(root.join(".gitignore"), "vendor/\n").expect("Failed to write .gitignore");
+    let content = r#"<div className="text-red-500 p-4 flex">test</div>"#;
+    fs::write(root.join("src/components/ui/Button.tsx"), content).expect("Failed to write test file");
+    fs::write(root.join("src/vendor/Lib.tsx"), content).expect("Failed to write test file");
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .current_dir(root)
+        .args(["--write", "src"])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    
+    let button = fs::read_to_string(root.join("src/components/ui/Button.tsx")).expect("Failed to read file");
+    assert!(button.contains(r#"className="flex p-4 text-red-500""#), 
+            "Nested files should be formatted. Got: {button}");
+    let vendor = fs::read_to_string(root.join("src/vendor/Lib.tsx")).expect("Failed to read file");
+    assert_eq!(vendor, content, "Gitignored files should be skipped");
+}
+
+#[test]
+fn test_cli_expands_globs_with_exclude() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src/builder")).expect("Failed to create dirs");
+    let content = r#"<div className="text-red-500 p-4 flex">test</div>"#;
+    fs::write(root.join("src/builder/Form.tsx"), content).expect("Failed to write test file");
+    fs::write(root.join("src/builder/Form.stories.tsx"), content).expect("Failed to write test file");
+    fs::write(root.join("src/index.html"), content).expect("Failed to write test file");
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .current_dir(root)
+        .args(["--write", "src/**/*.{tsx,vue}", "--exclude", "**/*.stories.tsx"])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    
+    let form = fs::read_to_string(root.join("src/builder/Form.tsx")).expect("Failed to read file");
+    assert!(form.contains(r#"className="flex p-4 text-red-500""#), 
+            "Files matching the glob should be formatted, even below a directory named like an ignore entry. Got: {form}");
+    let stories = fs::read_to_string(root.join("src/builder/Form.stories.tsx")).expect("Failed to read file");
+    assert_eq!(stories, content, "Excluded files should be skipped");
+    let html = fs::read_to_string(root.join("src/index.html")).expect("Failed to read file");
+    assert_eq!(html, content, "Files not matching the glob should be skipped");
+}
+
+#[test]
+fn test_cli_exclude_and_ignore_apply_to_named_files() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src/generated")).expect("Failed to create dirs");
+    fs::write(
+        root.join("tailwindsorter.config.json"),
+        r#"{ "ignore": ["generated"] }"#,
+    ).expect("Failed to write config file");
+    let content = r#"<div class="text-red-500 p-4 flex">test</div>"#;
+    fs::write(root.join("src/c.stories.html"), content).expect("Failed to write test file");
+    fs::write(root.join("src/d.html"), content).expect("Failed to write test file");
+    fs::write(root.join("src/generated/e.html"), content).expect("Failed to write test file");
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .current_dir(root)
+        .args(["--write", "src/c.stories.html", "src/d.html", "src/generated/e.html", "--exclude", "**/*.stories.html"])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    
+    let d = fs::read_to_string(root.join("src/d.html")).expect("Failed to read file");
+    assert!(d.contains(r#"class="flex p-4 text-red-500""#), "Named files should be formatted. Got: {d}");
+    let stories = fs::read_to_string(root.join("src/c.stories.html")).expect("Failed to read file");
+    assert_eq!(stories, content, "Named files matching --exclude should be skipped");
+    let generated = fs::read_to_string(root.join("src/generated/e.html")).expect("Failed to read file");
+    assert_eq!(generated, content, "Named files matching the ignore list should be skipped");
+}
+
+#[test]
+fn test_cli_stdin_writes_to_stdout() {
+    use std::io::Write;
+    use std::process::Stdio;
+    
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(
+        temp_dir.path().join("tailwindsorter.config.json"),
+        r#"{ "remove_duplicates": true }"#,
+    ).expect("Failed to write config file");
+    let file_path = temp_dir.path().join("Unsaved.tsx").to_string_lossy().to_string();
+    
+    let binary = get_binary_path();
+    let mut child = Command::new(&binary)
+        .args(["--stdin", "--stdin-filepath", &file_path, "--preserve-cursor", "--cursor-offset", "0"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    child.stdin.take().unwrap()
+        .write_all(br#"<div className="text-red-500 p-4 flex p-4">test</div>"#)
+        .expect("Failed to write stdin");
+    let output = child.wait_with_output().expect("Failed to wait for command");
+        
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        r#"<div className="flex p-4 text-red-500">test</div>"#,
+        "Stdout should only contain the formatted source, using the config next to the path"
+    );
+    assert!(!temp_dir.path().join("Unsaved.tsx").exists(), "Nothing should be written to disk");
+    
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("CURSOR_POSITION:0:0:0"), "Should report the cursor. Got: {stderr}");
+}
+
+#[test]
+fn test_cli_stdin_cursor_in_utf16_code_units() {
+    use std::io::Write;
+    use std::process::Stdio;
+    
+    let source = "<p>é😀</p>\r\n<div class=\"p-4 text-red-500 flex\">x</div>";
+    // UTF-16 offset of `x`, as VS Code's offsetAt reports it
+    let offset = source[..source.find(">x<").unwrap() + 1].encode_utf16().count();
+    
+    let binary = get_binary_path();
+    let mut child = Command::new(&binary)
+        .args(["--stdin", "--stdin-filepath", "a.html", "--preserve-cursor", "--cursor-encoding", "utf16"])
+        .args(["--cursor-offset", &offset.to_string()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    child.stdin.take().unwrap().write_all(source.as_bytes()).expect("Failed to write stdin");
+    let output = child.wait_with_output().expect("Failed to wait for command");
+    
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "<p>é😀</p>\r\n<div class=\"flex p-4 text-red-500\">x</div>"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(&format!("CURSOR_POSITION:1:35:{offset}")), "Should report UTF-16 columns. Got: {stderr}");
+}
+
+#[test]
+fn test_cli_check_with_diff() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let file_path = create_test_file(
+        "<main>\n  <div class=\"text-red-500 p-4 flex\">test</div>\n</main>\n",
+        &temp_dir
+    );
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .args(["--check", "--diff", &file_path])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 1, "Should exit with code 1 when formatting is needed");
+    
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let expected = format!(
+        "--- a/{file_path}\n+++ b/{file_path}\n@@ -1,3 +1,3 @@\n <main>\n-  <div class=\"text-red-500 p-4 flex\">test</div>\n+  <div class=\"flex p-4 text-red-500\">test</div>\n </main>\n"
+    );
+    assert!(stdout.contains(&expected), "Should print an uncolored diff when not on a terminal. Got: {stdout}");
+}
+
+#[test]
+fn test_cli_json_reporter() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let file_path = create_test_file(
+        "<main>\n  <div class=\"text-red-500 p-4 flex\">test</div>\n</main>\n",
+        &temp_dir
+    );
+    let missing_path = temp_dir.path().join("missing.html").to_string_lossy().to_string();
+    let sorted_path = temp_dir.path().join("sorted.html");
+    fs::write(&sorted_path, r#"<div class="flex p-4">test</div>"#).expect("Failed to write test file");
+    let sorted_path = sorted_path.to_string_lossy().to_string();
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .args(["--check", "--reporter", "json", &file_path, &sorted_path, &missing_path])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 1, "Should exit with code 1 when formatting is needed");
+    
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .expect("Stdout should be a single JSON document");
+    let files = report["files"].as_array().expect("Should list files");
+    assert_eq!(files.len(), 2, "Missing paths are not processed");
+    assert_eq!(files[0]["path"], file_path.as_str());
+    assert_eq!(files[0]["changed"], true);
+    let change = &files[0]["changes"][0];
+    assert_eq!((change["line"].as_u64(), change["column"].as_u64()), (Some(2), Some(8)));
+    assert_eq!(change["start"], 14);
+    assert_eq!(change["before"], "text-red-500 p-4 flex");
+    assert_eq!(change["after"], "flex p-4 text-red-500");
+    assert_eq!(files[1]["changed"], false);
+    assert_eq!(
+        report["summary"],
+        serde_json::json!({ "files": 2, "changed": 1, "unchanged": 1, "errors": 0, "write": false })
+    );
+}
+
+#[test]
+fn test_cli_sarif_and_github_reporters() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    create_test_file("<main>\n  <div class=\"text-red-500 p-4 flex\">test</div>\n</main>\n", &temp_dir);
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .current_dir(temp_dir.path())
+        .args(["--check", "--reporter", "sarif", "test.html"])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 1, "Should exit with code 1 when formatting is needed");
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .expect("Stdout should be a SARIF log");
+    assert_eq!(log["version"], "2.1.0");
+    assert_eq!(log["runs"][0]["columnKind"], "utf16CodeUnits");
+    let results = log["runs"][0]["results"].as_array().expect("Should list results");
+    assert_eq!(results.len(), 1, "Should report one result per unsorted attribute");
+    assert_eq!(results[0]["ruleId"], "unsorted-classes");
+    let location = &results[0]["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "test.html");
+    assert_eq!((location["region"]["startLine"].as_u64(), location["region"]["startColumn"].as_u64()), (Some(2), Some(8)));
+    
+    let output = Command::new(&binary)
+        .current_dir(temp_dir.path())
+        .args(["--check", "--reporter", "github", "test.html"])
+        .output()
+        .expect("Failed to execute command");
+        
+    assert_eq!(output.status.code().unwrap(), 1, "Should exit with code 1 when formatting is needed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("::warning file=test.html,line=2,col=8,endLine=2,endColumn=37,"),
+        "Should print a workflow command per unsorted attribute. Got: {stdout}"
+    );
+    assert!(stdout.contains("expected `flex p-4 text-red-500`"), "Got: {stdout}");
+}
+
+#[test]
+fn test_cli_threads_keep_output_order() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let mut paths = Vec::new();
+    for index in 0..20 {
+        let path = temp_dir.path().join(format!("page{index:02}.html"));
+        let class = if index % 2 == 0 { "p-4 flex" } else { "flex p-4" };
+        fs::write(&path, format!("<div class=\"{class}\">{index}</div>")).expect("Failed to write test file");
+        paths.push(path.to_string_lossy().to_string());
+    }
+    
+    let binary = get_binary_path();
+    let run = |threads: &str| {
+        Command::new(&binary)
+            .args(["--check", "--verbose", "--threads", threads])
+            .arg(temp_dir.path())
+            .output()
+            .expect("Failed to execute command")
+    };
+    let sequential = run("1");
+    let parallel = run("8");
+    
+    assert_eq!(parallel.status.code().unwrap(), 1, "Should exit with code 1 when formatting is needed");
+    assert_eq!(
+        String::from_utf8_lossy(&parallel.stdout),
+        String::from_utf8_lossy(&sequential.stdout),
+        "Output should not depend on the number of threads"
+    );
+    let stdout = String::from_utf8_lossy(&parallel.stdout);
+    let positions: Vec<usize> = paths.iter().map(|path| stdout.find(path.as_str()).expect("Each file is reported")).collect();
+    assert!(positions.windows(2).all(|pair| pair[0] < pair[1]), "Files should be reported in order. Got: {stdout}");
+    assert!(stdout.contains("10 files need formatting"), "Got: {stdout}");
+}
+
+#[test]
+fn test_cli_cache_skips_formatted_files() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    create_test_file(r#"<div class="text-red-500 p-4 flex">test</div>"#, &temp_dir);
+    fs::write(temp_dir.path().join("sorted.html"), r#"<div class="flex p-4">test</div>"#)
+        .expect("Failed to write test file");
+    fs::create_dir(temp_dir.path().join("node_modules")).expect("Failed to create node_modules");
+    
+    let binary = get_binary_path();
+    let run = |args: &[&str]| {
+        let output = Command::new(&binary)
+            .current_dir(temp_dir.path())
+            .args(["--verbose", "."])
+            .args(args)
+            .output()
+            .expect("Failed to execute command");
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    
+    let stdout = run(&[]);
+    assert!(stdout.contains("Cache: 0 of 2 files unchanged"), "Got: {stdout}");
+    assert!(temp_dir.path().join("node_modules/.cache/biome-tailwind-sorter/cache.json").is_file());
+    
+    let stdout = run(&[]);
+    assert!(stdout.contains("Cache: 1 of 2 files unchanged"), "Only formatted files are cached. Got: {stdout}");
+    assert!(stdout.contains("test.html needs formatting"), "Got: {stdout}");
+    
+    run(&["--write"]);
+    let stdout = run(&[]);
+    assert!(stdout.contains("Cache: 2 of 2 files unchanged"), "Written files are cached. Got: {stdout}");
+    
+    // A different config invalidates the entries
+    let stdout = run(&["--remove-duplicates"]);
+    assert!(stdout.contains("Cache: 0 of 2 files unchanged"), "Got: {stdout}");
+    
+    let stdout = run(&["--no-cache"]);
+    assert!(!stdout.contains("Cache:"), "Got: {stdout}");
+    
+    let custom_dir = temp_dir.path().join("custom-cache");
+    run(&["--cache-location", custom_dir.to_str().unwrap()]);
+    assert!(custom_dir.join("cache.json").is_file());
+}
+
+#[test]
+fn test_cli_staged_and_since() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    let git = |args: &[&str]| {
+        let output = Command::new("git")
+            .current_dir(root)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .expect("Failed to run git");
+        assert!(output.status.success(), "git {args:?} failed: {}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    git(&["init", "-q"]);
+    fs::write(root.join("committed.html"), "<div class=\"p-4 flex\">old</div>\n").expect("Failed to write file");
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "initial"]);
+    
+    fs::write(root.join("staged.html"), "<div class=\"p-4 flex\">staged</div>\n").expect("Failed to write file");
+    fs::write(root.join("partial.html"), "<div class=\"p-4 flex\">partial</div>\n").expect("Failed to write file");
+    git(&["add", "staged.html", "partial.html"]);
+    fs::write(root.join("partial.html"), "<div class=\"p-4 flex\">partial</div>\n<p class=\"m-2 block\">wip</p>\n")
+        .expect("Failed to write file");
+    fs::write(root.join("untracked.html"), "<div class=\"p-4 flex\">new</div>\n").expect("Failed to write file");
+    
+    let binary = get_binary_path();
+    let run = |args: &[&str]| {
+        Command::new(&binary)
+            .current_dir(root)
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+    
+    let output = run(&["--staged", "--check", "--verbose"]);
+    assert_eq!(output.status.code().unwrap(), 1, "Staged files need formatting");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("staged.html needs formatting") && stdout.contains("partial.html needs formatting"), "Got: {stdout}");
+    assert!(!stdout.contains("committed.html") && !stdout.contains("untracked.html"), "Got: {stdout}");
+    
+    let output = run(&["--staged", "--write"]);
+    assert!(output.status.success(), "Got: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("partial.html has unstaged changes"));
+    assert_eq!(git(&["show", ":staged.html"]), "<div class=\"flex p-4\">staged</div>\n");
+    assert_eq!(fs::read_to_string(root.join("staged.html")).unwrap(), "<div class=\"flex p-4\">staged</div>\n");
+    assert_eq!(git(&["show", ":partial.html"]), "<div class=\"flex p-4\">partial</div>\n");
+    assert_eq!(
+        fs::read_to_string(root.join("partial.html")).unwrap(),
+        "<div class=\"p-4 flex\">partial</div>\n<p class=\"m-2 block\">wip</p>\n",
+        "The working tree of a partially staged file is left alone"
+    );
+    assert_eq!(fs::read_to_string(root.join("untracked.html")).unwrap(), "<div class=\"p-4 flex\">new</div>\n");
+    assert!(run(&["--staged", "--check"]).status.success(), "Restaged content is formatted");
+    
+    let output = run(&["--since", "HEAD", "--check", "--verbose"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("partial.html needs formatting") && stdout.contains("untracked.html needs formatting"), "Got: {stdout}");
+    assert!(stdout.contains("staged.html is already formatted") && !stdout.contains("committed.html"), "Got: {stdout}");
+    
+    git(&["commit", "-q", "-m", "second"]);
+    git(&["stash", "-q", "--include-untracked"]);
+    let output = run(&["--staged", "--check"]);
+    assert!(output.status.success(), "Nothing staged is not an error");
+}
+
+#[test]
+fn test_cli_lines_and_range() {
+    use std::io::Write;
+    use std::process::Stdio;
+    
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let source = "<div class=\"p-4 flex\">\n  <p class=\"m-2 block\">a</p>\n  <p class=\"m-1 grid\">b</p>\n</div>\n";
+    let file_path = create_test_file(source, &temp_dir);
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .args(["--write", "--lines", "2-2", &file_path])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(&file_path).unwrap(),
+        "<div class=\"p-4 flex\">\n  <p class=\"block m-2\">a</p>\n  <p class=\"m-1 grid\">b</p>\n</div>\n",
+        "Only the attribute on line 2 should be formatted"
+    );
+    
+    let mut child = Command::new(&binary)
+        .args(["--stdin", "--stdin-filepath", "index.html", "--range", "5:10"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    child.stdin.take().unwrap().write_all(source.as_bytes()).expect("Failed to write stdin");
+    let output = child.wait_with_output().expect("Failed to wait for command");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "<div class=\"flex p-4\">\n  <p class=\"m-2 block\">a</p>\n  <p class=\"m-1 grid\">b</p>\n</div>\n"
+    );
+}
+
+#[test]
+fn test_cli_watch_formats_changed_files() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+    
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let file_path = create_test_file(r#"<div class="flex p-4">test</div>"#, &temp_dir);
+    
+    let binary = get_binary_path();
+    let mut child = Command::new(&binary)
+        .args(["--watch", "--write"])
+        .arg(temp_dir.path())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    let (sender, lines) = mpsc::channel();
+    let stdout = child.stdout.take().unwrap();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            let _ = sender.send(line);
+        }
+    });
+    let wait_for = |needle: &str| {
+        let deadline = Instant::now() + Duration::from_secs(20);
+        while let Ok(line) = lines.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            if line.contains(needle) {
+                return true;
+            }
+        }
+        false
+    };
+    assert!(wait_for("Watching for changes"), "Should start watching");
+    
+    fs::write(&file_path, r#"<div class="text-red-500 p-4 flex">changed</div>"#).expect("Failed to write file");
+    let new_file = temp_dir.path().join("new.html");
+    fs::write(&new_file, r#"<p class="m-2 block">new</p>"#).expect("Failed to write file");
+    assert!(wait_for("Formatted"), "Should format changed files");
+    assert!(wait_for("Formatted"), "Should format new files");
+    
+    // Its own writes must not trigger another round
+    std::thread::sleep(Duration::from_millis(1500));
+    let _ = child.kill();
+    let _ = child.wait();
+    let extra: Vec<String> = lines.try_iter().collect();
+    assert!(extra.is_empty(), "Should not process its own writes. Got: {extra:?}");
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), r#"<div class="flex p-4 text-red-500">changed</div>"#);
+    assert_eq!(fs::read_to_string(&new_file).unwrap(), r#"<p class="block m-2">new</p>"#);
+}
+
+#[test]
+fn test_cli_errors_have_distinct_exit_codes() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let invalid = temp_dir.path().join("invalid.html");
+    fs::write(&invalid, b"<div class=\"p-4 flex\">\xff</div>").expect("Failed to write test file");
+    
+    let binary = get_binary_path();
+    let run = |file: &std::path::Path| {
+        Command::new(&binary)
+            .current_dir(temp_dir.path())
+            .arg("--check")
+            .arg(file)
+            .output()
+            .expect("Failed to execute command")
+    };
+    
+    let output = run(&invalid);
+    assert_eq!(output.status.code().unwrap(), 5, "Should exit with code 5 on invalid UTF-8");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is not valid UTF-8"));
+    
+    fs::create_dir(temp_dir.path().join("small")).expect("Failed to create dir");
+    fs::write(temp_dir.path().join("small/tailwindsorter.toml"), "max_file_size = 10\n")
+        .expect("Failed to write config file");
+    let large = temp_dir.path().join("small/large.html");
+    fs::write(&large, r#"<div class="p-4 flex">test</div>"#).expect("Failed to write test file");
+    let output = run(&large);
+    assert_eq!(output.status.code().unwrap(), 4, "Should exit with code 4 on too large files");
+}
+
+#[test]
+fn test_cli_preserves_encoding_bom_and_line_endings() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let latin1 = temp_dir.path().join("latin1.html");
+    fs::write(&latin1, b"<p class=\"p-4 flex\">caf\xE9</p>\r\n<p class=\"\r\n  m-2 block\r\n  grid p-4\r\n\">\xA9</p>\r\n")
+        .expect("Failed to write test file");
+    let bom = temp_dir.path().join("bom.html");
+    fs::write(&bom, "\u{FEFF}<div class=\"p-4 flex\">\r\n  caf\u{E9}\r\n</div>\r\n").expect("Failed to write test file");
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .arg("--write")
+        .arg(&latin1)
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code().unwrap(), 5, "Should exit with code 5 on invalid UTF-8");
+    
+    let output = Command::new(&binary)
+        .args(["--write", "--encoding", "latin1"])
+        .arg(&latin1)
+        .arg(&bom)
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code().unwrap(), 0, "Should exit with code 0");
+    
+    assert_eq!(
+        fs::read(&latin1).unwrap(),
+        b"<p class=\"flex p-4\">caf\xE9</p>\r\n<p class=\"\r\n  block grid\r\n  m-2 p-4\r\n\">\xA9</p>\r\n",
+        "Should only change the order of the classes"
+    );
+    assert_eq!(
+        fs::read_to_string(&bom).unwrap(),
+        "\u{FEFF}<div class=\"flex p-4\">\r\n  caf\u{E9}\r\n</div>\r\n",
+        "Should keep the byte order mark and the UTF-8 encoding it selects"
+    );
+}
+
+#[test]
+fn test_cli_offsets_count_bytes_of_the_file() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let bom = temp_dir.path().join("bom.html");
+    fs::write(&bom, "\u{FEFF}<a class=\"p-4 flex\">").expect("Failed to write test file");
+    let utf16 = temp_dir.path().join("utf16.html");
+    let bytes: Vec<u8> = "\u{FEFF}<a class=\"p-4 flex\">".encode_utf16().flat_map(u16::to_le_bytes).collect();
+    fs::write(&utf16, bytes).expect("Failed to write test file");
+    
+    let binary = get_binary_path();
+    let output = Command::new(&binary)
+        .args(["--check", "--reporter", "json"])
+        .arg(&bom)
+        .output()
+        .expect("Failed to execute command");
+        
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .expect("Stdout should be a single JSON document");
+    let change = &report["files"][0]["changes"][0];
+    assert_eq!((change["start"].as_u64(), change["end"].as_u64()), (Some(6), Some(22)), "Should count the byte order mark");
+    
+    let output = Command::new(&binary)
+        .args(["--check", "--reporter", "sarif"])
+        .arg(&bom)
+        .arg(&utf16)
+        .output()
+        .expect("Failed to execute command");
+        
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .expect("Stdout should be a SARIF log");
+    let results = log["runs"][0]["results"].as_array().expect("Should list results");
+    assert_eq!(results.len(), 2);
+    let region = &results[0]["locations"][0]["physicalLocation"]["region"];
+    assert_eq!((region["byteOffset"].as_u64(), region["byteLength"].as_u64()), (Some(6), Some(16)));
+    let region = &results[1]["locations"][0]["physicalLocation"]["region"];
+    assert_eq!(region["byteOffset"], serde_json::Value::Null, "Should leave out offsets into the decoded text");
+    assert_eq!(region["startColumn"], 4);
+    
+    // The attribute starts at byte 6, after the byte order mark
+    for (range, expected) in [("0:5", 0), ("0:7", 1)] {
+        let output = Command::new(&binary)
+            .args(["--check", "--range", range])
+            .arg(&bom)
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(output.status.code().unwrap(), expected, "Unexpected exit code for --range {range}");
+    }
+}
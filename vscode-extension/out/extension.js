@@ -102,14 +102,6 @@ class TailwindSorter {
         // Fallback to global installation
         return 'biome-tailwind-sorter';
     }
-    calculateOffset(document, position) {
-        let offset = 0;
-        for (let i = 0; i < position.line; i++) {
-            offset += document.lineAt(i).text.length + 1; // +1 for newline
-        }
-        offset += position.character;
-        return offset;
-    }
     parseNewCursorPosition(stderr) {
         const match = stderr.match(/CURSOR_POSITION:(\d+):(\d+):(\d+)/);
         if (match) {
@@ -122,14 +114,21 @@ class TailwindSorter {
     async sortTailwindClasses(document, preserveCursor = true, cursorPosition) {
         return new Promise((resolve, reject) => {
             const binaryPath = this.getBinaryPath();
-            const args = ['--write'];
+            // Format the buffer through stdin so unsaved changes are used and
+            // nothing is written to disk; the path picks the config and file type
+            const args = ['--stdin', '--stdin-filepath', document.fileName];
             if (preserveCursor && cursorPosition) {
-                const offset = this.calculateOffset(document, cursorPosition);
-                args.push('--preserve-cursor', '--cursor-offset', offset.toString());
+                // VS Code offsets and columns count UTF-16 code units, and
+                // offsetAt counts CRLF line endings as two
+                const offset = document.offsetAt(cursorPosition);
+                args.push(
+                    '--preserve-cursor',
+                    '--cursor-offset', offset.toString(),
+                    '--cursor-encoding', 'utf16'
+                );
             }
-            // Use stdin/stdout instead of file for better performance
-            args.push('-'); // Read from stdin
             const child = cp.spawn(binaryPath, args, {
+                cwd: vscode.workspace.getWorkspaceFolder(document.uri)?.uri.fsPath,
                 stdio: ['pipe', 'pipe', 'pipe']
             });
             let stdout = '';
@@ -49,15 +49,6 @@ class TailwindSorter {
         return 'biome-tailwind-sorter';
     }
     
-    private calculateOffset(document: vscode.TextDocument, position: vscode.Position): number {
-        let offset = 0;
-        for (let i = 0; i < position.line; i++) {
-            offset += document.lineAt(i).text.length + 1; // +1 for newline
-        }
-        offset += position.character;
-        return offset;
-    }
-    
     private parseNewCursorPosition(stderr: string): vscode.Position | null {
         const match = stderr.match(/CURSOR_POSITION:(\d+):(\d+):(\d+)/);
         if (match) {
@@ -75,17 +66,23 @@ class TailwindSorter {
     ): Promise<{ content: string; newCursorPosition?: vscode.Position }> {
         return new Promise((resolve, reject) => {
             const binaryPath = this.getBinaryPath();
-            const args = ['--write'];
+            // Format the buffer through stdin so unsaved changes are used and
+            // nothing is written to disk; the path picks the config and file type
+            const args = ['--stdin', '--stdin-filepath', document.fileName];
             
             if (preserveCursor && cursorPosition) {
-                const offset = this.calculateOffset(document, cursorPosition);
-                args.push('--preserve-cursor', '--cursor-offset', offset.toString());
+                // VS Code offsets and columns count UTF-16 code units, and
+                // offsetAt counts CRLF line endings as two
+                const offset = document.offsetAt(cursorPosition);
+                args.push(
+                    '--preserve-cursor',
+                    '--cursor-offset', offset.toString(),
+                    '--cursor-encoding', 'utf16'
+                );
             }
             
-            // Use stdin/stdout instead of file for better performance
-            args.push('-'); // Read from stdin
-            
             const child = cp.spawn(binaryPath, args, {
+                cwd: vscode.workspace.getWorkspaceFolder(document.uri)?.uri.fsPath,
                 stdio: ['pipe', 'pipe', 'pipe']
             });
             